@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// How the sampler decides whether to take an uphill move. Downhill moves are always taken
+/// and plateau moves follow `lateral_accept_prob`, whatever the rule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum AcceptanceRule {
+    /// Boltzmann-Metropolis: accept with probability `exp(-delta / temperature)`.
+    #[default]
+    Metropolis,
+    /// Threshold accepting: accept iff `delta` is below a threshold that starts at `initial`
+    /// and is multiplied by `decay` after every step. Deterministic; the temperature is unused.
+    Threshold { initial: f64, decay: f64 },
+    /// Great deluge: accept iff the new energy is below a water level that starts at
+    /// `initial` and is multiplied by `decay` after every step. Deterministic as well.
+    GreatDeluge { initial: f64, decay: f64 },
+}
+
+impl AcceptanceRule {
+    pub const ALL: [AcceptanceRule; 3] = [
+        AcceptanceRule::Metropolis,
+        AcceptanceRule::Threshold {
+            initial: 2.0,
+            decay: 0.9995,
+        },
+        AcceptanceRule::GreatDeluge {
+            initial: 30.0,
+            decay: 0.99995,
+        },
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AcceptanceRule::Metropolis => "metropolis",
+            AcceptanceRule::Threshold { .. } => "threshold",
+            AcceptanceRule::GreatDeluge { .. } => "great-deluge",
+        }
+    }
+
+    /// Threshold or water level before the first step; `None` under Metropolis.
+    pub fn initial_level(&self) -> Option<f64> {
+        match *self {
+            AcceptanceRule::Metropolis => None,
+            AcceptanceRule::Threshold { initial, .. }
+            | AcceptanceRule::GreatDeluge { initial, .. } => Some(initial),
+        }
+    }
+
+    /// Threshold or water level after one more step.
+    pub fn next_level(&self, level: f64) -> f64 {
+        match *self {
+            AcceptanceRule::Metropolis => level,
+            AcceptanceRule::Threshold { decay, .. } | AcceptanceRule::GreatDeluge { decay, .. } => {
+                level * decay
+            }
+        }
+    }
+
+    /// Decision of a deterministic rule for an uphill move from `energy` by `delta`, given
+    /// the current `level`; `None` under Metropolis, which needs the temperature and an RNG.
+    pub fn accepts(&self, energy: f64, delta: f64, level: f64) -> Option<bool> {
+        match self {
+            AcceptanceRule::Metropolis => None,
+            AcceptanceRule::Threshold { .. } => Some(delta < level),
+            AcceptanceRule::GreatDeluge { .. } => Some(energy + delta < level),
+        }
+    }
+}
+
+impl fmt::Display for AcceptanceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for AcceptanceRule {
+    type Err = String;
+
+    /// A rule name; `threshold` and `great-deluge` also accept `NAME:INITIAL:DECAY`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts = text.split(':');
+        let name = parts.next().unwrap_or_default();
+        let rule = AcceptanceRule::ALL
+            .into_iter()
+            .find(|rule| rule.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                format!("unknown acceptance rule {name:?} (metropolis, threshold, great-deluge)")
+            })?;
+        let parameters = parts
+            .map(|part| part.parse::<f64>().map_err(|err| format!("{part:?}: {err}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        match (rule, parameters.as_slice()) {
+            (_, []) => Ok(rule),
+            (AcceptanceRule::Threshold { .. }, &[initial, decay]) => {
+                Ok(AcceptanceRule::Threshold { initial, decay })
+            }
+            (AcceptanceRule::GreatDeluge { .. }, &[initial, decay]) => {
+                Ok(AcceptanceRule::GreatDeluge { initial, decay })
+            }
+            _ => Err(format!("unexpected parameters in acceptance rule {text:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_rules_compare_against_their_level() {
+        let threshold = AcceptanceRule::Threshold {
+            initial: 2.0,
+            decay: 0.5,
+        };
+        assert_eq!(threshold.initial_level(), Some(2.0));
+        assert_eq!(threshold.accepts(10.0, 1.5, 2.0), Some(true));
+        assert_eq!(threshold.accepts(10.0, 2.0, 2.0), Some(false));
+        assert_eq!(threshold.next_level(2.0), 1.0);
+        assert_eq!(threshold.accepts(10.0, 1.5, 1.0), Some(false));
+
+        let deluge = AcceptanceRule::GreatDeluge {
+            initial: 12.0,
+            decay: 0.5,
+        };
+        assert_eq!(deluge.accepts(10.0, 1.0, 12.0), Some(true));
+        assert_eq!(deluge.accepts(10.0, 2.0, 12.0), Some(false));
+        assert_eq!(deluge.accepts(3.0, 2.0, deluge.next_level(12.0)), Some(true));
+
+        let metropolis = AcceptanceRule::Metropolis;
+        assert_eq!((metropolis.initial_level(), metropolis.accepts(0.0, 1.0, 5.0)), (None, None));
+        assert_eq!(metropolis.next_level(5.0), 5.0);
+    }
+
+    #[test]
+    fn rules_parse_with_and_without_parameters() {
+        assert_eq!("metropolis".parse(), Ok(AcceptanceRule::Metropolis));
+        assert_eq!("threshold".parse(), Ok(AcceptanceRule::ALL[1]));
+        let parsed = "great-deluge:40:0.9".parse();
+        let expected = AcceptanceRule::GreatDeluge {
+            initial: 40.0,
+            decay: 0.9,
+        };
+        assert_eq!(parsed, Ok(expected));
+        assert!("metropolis:1:2".parse::<AcceptanceRule>().is_err());
+        assert!("threshold:1".parse::<AcceptanceRule>().is_err());
+        assert!("deluge".parse::<AcceptanceRule>().is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use crate::sudoku::TracePoint;
+
+/// Energy statistics of an equilibrium sample taken at one temperature.
+#[derive(Clone, Copy, Debug)]
+pub struct VariancePoint {
+    pub temperature: f64,
+    pub mean: f64,
+    pub variance: f64,
+}
+
+pub fn mean_and_variance(samples: &[f64]) -> (f64, f64) {
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+    let count = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / count;
+    let variance = samples.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / count;
+    (mean, variance)
+}
+
+/// Integrated autocorrelation time of a stationary series and the window it was summed over.
+#[derive(Clone, Copy, Debug)]
+pub struct Autocorrelation {
+    /// `1 + 2 Σ ρ(k)` for lags `1..=window`: roughly how many steps apart two samples must be
+    /// to count as independent.
+    pub tau: f64,
+    pub window: usize,
+}
+
+/// Window multiple for `integrated_autocorrelation_time` (Sokal's `c`).
+pub const AUTOCORRELATION_WINDOW_FACTOR: f64 = 5.0;
+
+/// Normalized autocorrelation `ρ(lag)` of the series: the autocovariance at `lag` over the
+/// variance, both with the mean removed and divided by the series length.
+pub fn autocorrelation(samples: &[f64], lag: usize) -> f64 {
+    let (mean, variance) = mean_and_variance(samples);
+    if variance == 0.0 || lag >= samples.len() {
+        return 0.0;
+    }
+    autocovariance(samples, mean, lag) / variance
+}
+
+/// Integrated autocorrelation time with Sokal's automatic window: the sum of `ρ(k)` stops at
+/// the first lag `W` with `W ≥ c·τ(W)`, which keeps the noisy tail of the estimator out.
+/// `None` for a series shorter than two samples or with no variance.
+pub fn integrated_autocorrelation_time(samples: &[f64]) -> Option<Autocorrelation> {
+    let (mean, variance) = mean_and_variance(samples);
+    if samples.len() < 2 || variance == 0.0 {
+        return None;
+    }
+    let mut tau = 1.0;
+    for lag in 1..samples.len() {
+        tau += 2.0 * autocovariance(samples, mean, lag) / variance;
+        if lag as f64 >= AUTOCORRELATION_WINDOW_FACTOR * tau {
+            return Some(Autocorrelation { tau, window: lag });
+        }
+    }
+    Some(Autocorrelation {
+        tau,
+        window: samples.len() - 1,
+    })
+}
+
+fn autocovariance(samples: &[f64], mean: f64, lag: usize) -> f64 {
+    let sum: f64 = samples
+        .iter()
+        .zip(&samples[lag..])
+        .map(|(a, b)| (a - mean) * (b - mean))
+        .sum();
+    sum / samples.len() as f64
+}
+
+/// Running totals of a batch of solves, updated as each one finishes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BatchTally {
+    pub done: usize,
+    pub solved: usize,
+    /// Mean swaps per finished solve, kept as a running mean.
+    pub mean_steps: f64,
+    /// Highest best energy among the finished solves.
+    pub worst_energy: usize,
+}
+
+impl BatchTally {
+    pub fn record(&mut self, best_energy: usize, steps: usize) {
+        self.done += 1;
+        self.solved += usize::from(best_energy == 0);
+        self.worst_energy = self.worst_energy.max(best_energy);
+        self.mean_steps += (steps as f64 - self.mean_steps) / self.done as f64;
+    }
+
+    /// Share of finished solves that reached zero energy; `None` before the first finishes.
+    pub fn solve_rate(&self) -> Option<f64> {
+        (self.done > 0).then(|| self.solved as f64 / self.done as f64)
+    }
+}
+
+/// A stretch of a run in which the best energy did not improve.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plateau {
+    /// Swap at which the best energy last improved (or the first traced swap).
+    pub start_step: usize,
+    /// Swap of the next improvement, or the end of the run.
+    pub end_step: usize,
+    /// Best energy held throughout.
+    pub best_energy: usize,
+    /// Lowest and highest temperature traced within the stretch.
+    pub low_temp: f64,
+    pub high_temp: f64,
+}
+
+impl Plateau {
+    pub fn steps(&self) -> usize {
+        self.end_step - self.start_step
+    }
+}
+
+/// Splits a trace at every improvement of the best energy and returns the stretches in
+/// between, longest first (ties keep run order). The last stretch runs to the end of the
+/// trace, so a run that stalls out shows up as its final plateau. Only traced points are
+/// seen: with `trace_every` above 1 the boundaries are as coarse as the trace.
+pub fn analyze_plateaus(trace: &[TracePoint]) -> Vec<Plateau> {
+    let Some(first) = trace.first() else {
+        return Vec::new();
+    };
+    let open = |point: &TracePoint| Plateau {
+        start_step: point.step,
+        end_step: point.step,
+        best_energy: point.best_energy,
+        low_temp: point.temperature,
+        high_temp: point.temperature,
+    };
+    let mut plateaus = Vec::new();
+    let mut current = open(first);
+    for point in &trace[1..] {
+        current.end_step = point.step;
+        if point.best_energy < current.best_energy {
+            plateaus.push(current);
+            current = open(point);
+        } else {
+            current.low_temp = current.low_temp.min(point.temperature);
+            current.high_temp = current.high_temp.max(point.temperature);
+        }
+    }
+    plateaus.push(current);
+    plateaus.retain(|plateau| plateau.steps() > 0);
+    plateaus.sort_by_key(|plateau| std::cmp::Reverse(plateau.steps()));
+    plateaus
+}
+
+/// Wilson score interval for a binomial success rate, `z` standard errors wide (1.96 for
+/// 95%). Unlike the normal approximation it stays inside `[0, 1]` and behaves at 0 or M
+/// successes. With no trials nothing is known, so the interval is `[0, 1]`.
+pub fn wilson_interval(successes: usize, trials: usize, z: f64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let n = trials as f64;
+    let rate = successes as f64 / n;
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / n;
+    let centre = (rate + z2 / (2.0 * n)) / denominator;
+    let half_width = z * (rate * (1.0 - rate) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+    ((centre - half_width).max(0.0), (centre + half_width).min(1.0))
+}
+
+/// Locates the specific-heat peak: sweeps `points` evenly spaced temperatures over
+/// `[low, high]`, then repeatedly re-sweeps the interval between the neighbours of the
+/// highest-variance point. `sample` returns equilibrium energies at a given temperature.
+/// Returns the estimated critical temperature and every measured point, sorted by temperature.
+pub fn find_critical_temperature(
+    low: f64,
+    high: f64,
+    points: usize,
+    refinements: usize,
+    mut sample: impl FnMut(f64) -> Vec<f64>,
+) -> (f64, Vec<VariancePoint>) {
+    let points = points.max(3);
+    let mut curve: Vec<VariancePoint> = Vec::new();
+    let (mut low, mut high) = (low.min(high), low.max(high));
+    for _ in 0..=refinements {
+        let spacing = (high - low) / (points - 1) as f64;
+        let sweep: Vec<VariancePoint> = (0..points)
+            .map(|index| {
+                let temperature = low + spacing * index as f64;
+                let (mean, variance) = mean_and_variance(&sample(temperature));
+                VariancePoint {
+                    temperature,
+                    mean,
+                    variance,
+                }
+            })
+            .collect();
+        let peak = peak_index(&sweep);
+        low = sweep[peak.saturating_sub(1)].temperature;
+        high = sweep[(peak + 1).min(points - 1)].temperature;
+        curve.extend(sweep);
+    }
+    curve.sort_by(|a, b| a.temperature.total_cmp(&b.temperature));
+    let critical = curve[peak_index(&curve)].temperature;
+    (critical, curve)
+}
+
+fn peak_index(points: &[VariancePoint]) -> usize {
+    points
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.variance.total_cmp(&b.variance))
+        .map_or(0, |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn critical_temperature_finds_a_synthetic_variance_peak() {
+        // Two samples at ±s have variance s², so this landscape peaks at T = 1.37.
+        let landscape = |temperature: f64| {
+            let spread = (1.0 / (1.0 + 50.0 * (temperature - 1.37).powi(2))).sqrt();
+            vec![-spread, spread]
+        };
+        let (critical, curve) = find_critical_temperature(0.2, 3.0, 9, 4, landscape);
+        assert!((critical - 1.37).abs() < 0.02, "{critical}");
+        assert_eq!(curve.len(), 9 * 5);
+        assert!(curve.windows(2).all(|pair| pair[0].temperature <= pair[1].temperature));
+    }
+
+    #[test]
+    fn wilson_interval_matches_reference_values() {
+        let close = |(low, high): (f64, f64), (want_low, want_high): (f64, f64)| {
+            assert!((low - want_low).abs() < 1e-4 && (high - want_high).abs() < 1e-4);
+        };
+        close(wilson_interval(0, 10, 1.96), (0.0, 0.2775));
+        close(wilson_interval(5, 10, 1.96), (0.2366, 0.7634));
+        close(wilson_interval(10, 10, 1.96), (0.7225, 1.0));
+        assert_eq!(wilson_interval(0, 0, 1.96), (0.0, 1.0));
+        let (narrow, wide) = (wilson_interval(50, 100, 1.96), wilson_interval(5, 10, 1.96));
+        assert!(narrow.1 - narrow.0 < wide.1 - wide.0);
+    }
+
+    /// `x[t] = phi * x[t - 1] + noise`, whose autocorrelation is `phi^lag`.
+    fn ar1(phi: f64, len: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut value = 0.0;
+        (0..len)
+            .map(|_| {
+                value = phi * value + rng.random_range(-1.0..1.0);
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn autocorrelation_time_of_an_ar1_series_matches_theory() {
+        for phi in [0.5, 0.8] {
+            let samples = ar1(phi, 200_000, 26);
+            assert!((autocorrelation(&samples, 1) - phi).abs() < 0.01);
+            assert!((autocorrelation(&samples, 3) - phi.powi(3)).abs() < 0.02);
+            let estimate = integrated_autocorrelation_time(&samples).unwrap();
+            let expected = (1.0 + phi) / (1.0 - phi);
+            assert!((estimate.tau - expected).abs() < 0.1 * expected, "{phi}: {estimate:?}");
+            assert!(estimate.window as f64 >= AUTOCORRELATION_WINDOW_FACTOR * estimate.tau);
+        }
+        let white = integrated_autocorrelation_time(&ar1(0.0, 100_000, 27)).unwrap();
+        assert!((white.tau - 1.0).abs() < 0.1, "{white:?}");
+        assert!(integrated_autocorrelation_time(&[3.0; 10]).is_none());
+    }
+
+    #[test]
+    fn batch_tally_keeps_a_running_mean_and_solve_rate() {
+        let mut tally = BatchTally::default();
+        assert_eq!(tally.solve_rate(), None);
+        for (best_energy, steps) in [(0, 100), (4, 400), (0, 250), (2, 50)] {
+            tally.record(best_energy, steps);
+        }
+        assert_eq!((tally.done, tally.solved, tally.worst_energy), (4, 2, 4));
+        assert!((tally.mean_steps - 200.0).abs() < 1e-9);
+        assert_eq!(tally.solve_rate(), Some(0.5));
+    }
+
+    #[test]
+    fn plateaus_span_the_flat_stretches_of_a_trace() {
+        let temperature = |step: usize| 3.0 - 0.02 * step as f64;
+        let best = |step: usize| match step {
+            0..10 => 10,
+            10..80 => 8,
+            _ => 5,
+        };
+        let trace: Vec<TracePoint> = (0..=100)
+            .step_by(10)
+            .map(|step| TracePoint {
+                step,
+                energy: best(step) + 2,
+                best_energy: best(step),
+                temperature: temperature(step),
+            })
+            .collect();
+        let plateau = |start_step, end_step, best_energy, temps: (usize, usize)| Plateau {
+            start_step,
+            end_step,
+            best_energy,
+            low_temp: temperature(temps.1),
+            high_temp: temperature(temps.0),
+        };
+        let expected = [
+            plateau(10, 80, 8, (10, 70)),
+            plateau(80, 100, 5, (80, 100)),
+            plateau(0, 10, 10, (0, 0)),
+        ];
+        assert_eq!(analyze_plateaus(&trace), expected);
+        assert_eq!(analyze_plateaus(&trace)[0].steps(), 70);
+        assert!(analyze_plateaus(&[]).is_empty());
+    }
+}
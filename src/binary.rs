@@ -0,0 +1,209 @@
+use std::{error::Error, fmt};
+
+use crate::sudoku::{SudokuPuzzle, SudokuState};
+
+/// Leading bytes of every packed puzzle or board.
+pub const MAGIC: [u8; 4] = *b"TCSK";
+/// Bumped whenever the packed layout changes.
+pub const FORMAT_VERSION: u8 = 1;
+
+const KIND_PUZZLE: u8 = 0;
+const KIND_BOARD: u8 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 2;
+const BITMAP_LEN: usize = 81usize.div_ceil(8);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BinaryFormatError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    /// A packed board was read as a puzzle, or the reverse.
+    WrongKind(u8),
+    /// A packed section (bitmap or digits) has the wrong number of bytes.
+    WrongLength { expected: usize, found: usize },
+    InvalidDigit(u8),
+}
+
+impl fmt::Display for BinaryFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryFormatError::BadMagic => write!(f, "not a packed Sudoku (bad magic)"),
+            BinaryFormatError::UnsupportedVersion(version) => {
+                write!(f, "unsupported packed format version {version}")
+            }
+            BinaryFormatError::WrongKind(kind) => write!(f, "unexpected record kind {kind}"),
+            BinaryFormatError::WrongLength { expected, found } => {
+                write!(f, "packed section has {found} bytes, expected {expected}")
+            }
+            BinaryFormatError::InvalidDigit(digit) => write!(f, "invalid packed digit {digit}"),
+        }
+    }
+}
+
+impl Error for BinaryFormatError {}
+
+impl SudokuPuzzle {
+    /// Header, an 81-bit bitmap of which cells are given, then the given digits in row-major
+    /// order at 4 bits each: 17 to 58 bytes instead of 81 characters.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bitmap = [0u8; BITMAP_LEN];
+        let mut digits = Vec::new();
+        for (index, given) in self.givens.iter().flatten().enumerate() {
+            if let Some(value) = given {
+                bitmap[index / 8] |= 1 << (index % 8);
+                digits.push(*value);
+            }
+        }
+        let mut bytes = header(KIND_PUZZLE);
+        bytes.extend_from_slice(&bitmap);
+        bytes.extend(pack_nibbles(&digits));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryFormatError> {
+        let body = read_header(bytes, KIND_PUZZLE)?;
+        if body.len() < BITMAP_LEN {
+            return Err(BinaryFormatError::WrongLength {
+                expected: BITMAP_LEN,
+                found: body.len(),
+            });
+        }
+        let (bitmap, packed) = body.split_at(BITMAP_LEN);
+        let present: Vec<usize> = (0..81)
+            .filter(|&index| bitmap[index / 8] >> (index % 8) & 1 == 1)
+            .collect();
+        let digits = unpack_nibbles(packed, present.len())?;
+        let mut givens = [[None; 9]; 9];
+        for (&index, &value) in present.iter().zip(&digits) {
+            givens[index / 9][index % 9] = Some(value);
+        }
+        Ok(SudokuPuzzle { givens })
+    }
+}
+
+impl SudokuState {
+    /// Header followed by all 81 digits at 4 bits each (47 bytes).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let digits: Vec<u8> = self.board.iter().flatten().copied().collect();
+        let mut bytes = header(KIND_BOARD);
+        bytes.extend(pack_nibbles(&digits));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryFormatError> {
+        let digits = unpack_nibbles(read_header(bytes, KIND_BOARD)?, 81)?;
+        let mut board = [[0u8; 9]; 9];
+        for (index, value) in digits.into_iter().enumerate() {
+            board[index / 9][index % 9] = value;
+        }
+        Ok(SudokuState { board })
+    }
+}
+
+fn header(kind: u8) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(FORMAT_VERSION);
+    bytes.push(kind);
+    bytes
+}
+
+/// Validates magic, version, and kind, returning the bytes after the header.
+fn read_header(bytes: &[u8], kind: u8) -> Result<&[u8], BinaryFormatError> {
+    if bytes.len() < HEADER_LEN || bytes[..MAGIC.len()] != MAGIC {
+        return Err(BinaryFormatError::BadMagic);
+    }
+    let version = bytes[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(BinaryFormatError::UnsupportedVersion(version));
+    }
+    let found_kind = bytes[MAGIC.len() + 1];
+    if found_kind != kind {
+        return Err(BinaryFormatError::WrongKind(found_kind));
+    }
+    Ok(&bytes[HEADER_LEN..])
+}
+
+/// Two digits per byte, low nibble first; an odd count leaves the last high nibble zero.
+fn pack_nibbles(digits: &[u8]) -> Vec<u8> {
+    digits
+        .chunks(2)
+        .map(|pair| pair[0] | pair.get(1).map_or(0, |high| high << 4))
+        .collect()
+}
+
+fn unpack_nibbles(packed: &[u8], count: usize) -> Result<Vec<u8>, BinaryFormatError> {
+    let expected = count.div_ceil(2);
+    if packed.len() != expected {
+        return Err(BinaryFormatError::WrongLength {
+            expected,
+            found: packed.len(),
+        });
+    }
+    (0..count)
+        .map(|index| {
+            let byte = packed[index / 2];
+            let digit = if index % 2 == 0 { byte & 0x0f } else { byte >> 4 };
+            if (1..=9).contains(&digit) {
+                Ok(digit)
+            } else {
+                Err(BinaryFormatError::InvalidDigit(digit))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn puzzles_and_boards_round_trip() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for holes in [0, 45, 81] {
+            let puzzle = SudokuPuzzle::with_random_holes(holes, &mut rng);
+            let bytes = puzzle.to_bytes();
+            assert_eq!(bytes.len(), HEADER_LEN + BITMAP_LEN + (81 - holes).div_ceil(2));
+            assert_eq!(SudokuPuzzle::from_bytes(&bytes).unwrap().givens, puzzle.givens);
+        }
+        let puzzle = SudokuPuzzle::reproduce(7, 45);
+        let state = puzzle.random_initial_state(&mut rng);
+        let bytes = state.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + 41);
+        assert_eq!(SudokuState::from_bytes(&bytes).unwrap().board, state.board);
+    }
+
+    #[test]
+    fn malformed_bytes_are_rejected() {
+        let puzzle = SudokuPuzzle::reproduce(8, 40).to_bytes();
+        let mut rng = StdRng::seed_from_u64(8);
+        let board = SudokuPuzzle::reproduce(8, 0).random_initial_state(&mut rng).to_bytes();
+
+        let mut bad_magic = puzzle.clone();
+        bad_magic[0] = b'X';
+        let expected = Some(BinaryFormatError::BadMagic);
+        assert_eq!(SudokuPuzzle::from_bytes(&bad_magic).err(), expected);
+        assert_eq!(SudokuPuzzle::from_bytes(&MAGIC).err(), expected);
+
+        let mut future = puzzle.clone();
+        future[MAGIC.len()] = FORMAT_VERSION + 1;
+        let expected = BinaryFormatError::UnsupportedVersion(FORMAT_VERSION + 1);
+        assert_eq!(SudokuPuzzle::from_bytes(&future).err(), Some(expected));
+
+        let expected = BinaryFormatError::WrongKind(KIND_BOARD);
+        assert_eq!(SudokuPuzzle::from_bytes(&board).err(), Some(expected));
+        let expected = BinaryFormatError::WrongKind(KIND_PUZZLE);
+        assert_eq!(SudokuState::from_bytes(&puzzle).err(), Some(expected));
+
+        let truncated = &board[..board.len() - 1];
+        let expected = BinaryFormatError::WrongLength {
+            expected: 41,
+            found: 40,
+        };
+        assert_eq!(SudokuState::from_bytes(truncated).err(), Some(expected));
+
+        let mut zero_digit = board.clone();
+        zero_digit[HEADER_LEN] &= 0xf0;
+        let expected = BinaryFormatError::InvalidDigit(0);
+        assert_eq!(SudokuState::from_bytes(&zero_digit).err(), Some(expected));
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::LazyLock;
+
+static CORPUS: LazyLock<Vec<(&'static str, &'static str)>> = LazyLock::new(|| {
+    include_str!("corpus.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .map(|(name, puzzle)| (name, puzzle.trim()))
+        .collect()
+});
+
+/// Embedded `(name, 81-cell puzzle)` pairs graded from easy to extreme, each with exactly one
+/// solution, so benchmarks have a baseline independent of the random generator.
+pub fn corpus() -> &'static [(&'static str, &'static str)] {
+    &CORPUS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{self, SudokuPuzzle};
+
+    #[test]
+    fn every_corpus_puzzle_parses_with_exactly_one_solution() {
+        assert!(!corpus().is_empty());
+        for &(name, text) in corpus() {
+            let puzzle = SudokuPuzzle::parse(text).unwrap_or_else(|err| panic!("{name}: {err}"));
+            assert_eq!(sudoku::count_solutions(&puzzle.givens, 2), 1, "{name}");
+        }
+    }
+}
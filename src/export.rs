@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{queens, sudoku};
+
+/// Bumped whenever a field of a JSON report is renamed, removed, or changes meaning.
+pub const JSON_SCHEMA_VERSION: u32 = 2;
+
+/// Everything needed to reproduce an exported artifact months later.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Provenance<C> {
+    pub crate_version: String,
+    /// `--seed`, which drives whatever `gen_seed` and `solve_seed` do not override.
+    pub seed: Option<u64>,
+    /// Seed the puzzle was generated from; `None` when nothing was generated.
+    #[serde(default)]
+    pub gen_seed: Option<u64>,
+    /// Seed the solve ran from.
+    #[serde(default)]
+    pub solve_seed: Option<u64>,
+    pub generated_at_unix: u64,
+    pub config: C,
+}
+
+impl<C> Provenance<C> {
+    pub fn new(seed: Option<u64>, config: C) -> Self {
+        let generated_at_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Provenance {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            seed,
+            gen_seed: None,
+            solve_seed: seed,
+            generated_at_unix,
+            config,
+        }
+    }
+
+    /// Records the seeds generation and solving actually used, for runs that split them with
+    /// `--gen-seed` and `--solve-seed`.
+    pub fn with_seeds(self, gen_seed: Option<u64>, solve_seed: Option<u64>) -> Self {
+        Provenance {
+            gen_seed,
+            solve_seed,
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SudokuReport {
+    pub schema_version: u32,
+    pub provenance: Provenance<sudoku::SamplerConfig>,
+    pub holes: usize,
+    pub givens: [[Option<u8>; 9]; 9],
+    pub board: [[u8; 9]; 9],
+    pub solved: bool,
+    pub steps: usize,
+    pub best_energy: usize,
+    pub temperature: f64,
+    pub floor_reached_at: Option<usize>,
+}
+
+/// Every proposed swap of a Sudoku solve, written by `--transcript` and replayed without the
+/// RNG by `--apply-transcript`. The starting board is stored because drawing it uses the RNG.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transcript {
+    pub schema_version: u32,
+    pub provenance: Provenance<sudoku::SamplerConfig>,
+    pub givens: [[Option<u8>; 9]; 9],
+    pub initial_board: [[u8; 9]; 9],
+    pub moves: Vec<sudoku::MoveRecord>,
+    /// Best board the solve reported, which a replay must reproduce.
+    pub board: [[u8; 9]; 9],
+    pub best_energy: usize,
+}
+
+pub fn read_transcript(path: &Path) -> Result<Transcript, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let transcript: Transcript = serde_json::from_str(&text)
+        .map_err(|err| format!("{}: not a solve transcript ({err})", path.display()))?;
+    if transcript.schema_version > JSON_SCHEMA_VERSION {
+        return Err(format!(
+            "{}: schema version {} is newer than this build supports ({})",
+            path.display(),
+            transcript.schema_version,
+            JSON_SCHEMA_VERSION,
+        )
+        .into());
+    }
+    Ok(transcript)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueensReport {
+    pub schema_version: u32,
+    pub provenance: Provenance<queens::QueensConfig>,
+    pub target: usize,
+    pub solutions: Vec<[u8; 8]>,
+    /// Unique solutions found; exceeds `solutions.len()` when `--max-store` dropped some.
+    pub found: usize,
+    pub restarts: usize,
+    pub total_steps: usize,
+    pub timed_out: bool,
+    pub floor_reached_at: Option<usize>,
+}
+
+/// One line of `bench --json-stream`: a complete result for a single puzzle of the batch.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub schema_version: u32,
+    /// Position of the puzzle in the batch, since lines arrive in completion order.
+    pub index: usize,
+    pub name: String,
+    /// Seed of this puzzle's solve; with the givens and config it reproduces the result.
+    pub seed: u64,
+    pub givens: [[Option<u8>; 9]; 9],
+    pub board: [[u8; 9]; 9],
+    pub solved: bool,
+    pub steps: usize,
+    pub best_energy: usize,
+    pub temperature: f64,
+    pub elapsed_ms: f64,
+}
+
+/// The parts of a saved `--json` report needed to redraw it. Other fields are ignored, so
+/// reports from any schema version up to the current one load.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum SavedResult {
+    Sudoku(Box<SavedSudoku>),
+    Queens(SavedQueens),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavedSudoku {
+    pub schema_version: u32,
+    pub givens: [[Option<u8>; 9]; 9],
+    pub board: [[u8; 9]; 9],
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavedQueens {
+    pub schema_version: u32,
+    pub solutions: Vec<[u8; 8]>,
+    /// Absent from reports written before `--max-store` existed.
+    #[serde(default)]
+    pub found: Option<usize>,
+    #[serde(default)]
+    pub restarts: usize,
+    #[serde(default)]
+    pub total_steps: usize,
+}
+
+pub fn read_saved_result(path: &Path) -> Result<SavedResult, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let saved: SavedResult = serde_json::from_str(&text)
+        .map_err(|err| format!("{}: not a Sudoku or queens report ({err})", path.display()))?;
+    let schema_version = match &saved {
+        SavedResult::Sudoku(sudoku) => sudoku.schema_version,
+        SavedResult::Queens(queens) => queens.schema_version,
+    };
+    if schema_version > JSON_SCHEMA_VERSION {
+        return Err(format!(
+            "{}: schema version {schema_version} is newer than this build supports ({})",
+            path.display(),
+            JSON_SCHEMA_VERSION,
+        )
+        .into());
+    }
+    Ok(saved)
+}
+
+pub fn write_json<T: Serialize>(path: &Path, report: &T) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_string_pretty(report)?;
+    fs::write(path, json + "\n")?;
+    Ok(())
+}
+
+/// Writes `value` as a single JSON line and flushes, so a consumer sees it at once.
+pub fn write_json_line<W: Write, T: Serialize>(
+    out: &mut W,
+    value: &T,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{}", serde_json::to_string(value)?)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Writes a solve trace as JSON lines when `path` ends in `.jsonl`, led by a
+/// `{"provenance": ...}` line, otherwise as CSV with the provenance in a leading `#` comment.
+pub fn write_trace<C: Serialize>(
+    path: &Path,
+    provenance: &Provenance<C>,
+    trace: &[sudoku::TracePoint],
+) -> Result<(), Box<dyn Error>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        writeln!(out, "{{\"provenance\":{}}}", serde_json::to_string(provenance)?)?;
+        for point in trace {
+            writeln!(out, "{}", serde_json::to_string(point)?)?;
+        }
+    } else {
+        writeln!(out, "# provenance: {}", serde_json::to_string(provenance)?)?;
+        writeln!(out, "step,energy,best_energy,temperature")?;
+        for point in trace {
+            writeln!(
+                out,
+                "{},{},{},{}",
+                point.step, point.energy, point.best_energy, point.temperature
+            )?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provenance_records_the_seed_and_crate_version() {
+        let provenance = Provenance::new(Some(42), sudoku::SamplerConfig::default());
+        let json: serde_json::Value = serde_json::to_value(&provenance).unwrap();
+        assert_eq!(json["seed"], 42);
+        assert_eq!(json["crate_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["config"]["max_steps"], sudoku::DEFAULT_MAX_STEPS);
+    }
+
+    #[test]
+    fn both_trace_formats_lead_with_the_provenance() {
+        let provenance = Provenance::new(Some(7), sudoku::SamplerConfig::default());
+        let trace = [sudoku::TracePoint {
+            step: 1,
+            energy: 3,
+            best_energy: 3,
+            temperature: 2.0,
+        }];
+        let dir = std::env::temp_dir();
+        let jsonl = dir.join(format!("tc-{}-trace.jsonl", std::process::id()));
+        write_trace(&jsonl, &provenance, &trace).unwrap();
+        let text = fs::read_to_string(&jsonl).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["provenance"]["seed"], 7);
+        assert_eq!(first["provenance"]["crate_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(lines.len(), 2);
+        fs::remove_file(jsonl).unwrap();
+
+        let csv = dir.join(format!("tc-{}-trace.csv", std::process::id()));
+        write_trace(&csv, &provenance, &trace).unwrap();
+        let text = fs::read_to_string(&csv).unwrap();
+        assert!(text.starts_with("# provenance: {\"crate_version\":"));
+        assert!(text.contains("\"seed\":7"));
+        fs::remove_file(csv).unwrap();
+    }
+}
@@ -0,0 +1,13 @@
+#![allow(clippy::needless_range_loop)]
+
+pub mod acceptance;
+pub mod analysis;
+pub mod binary;
+pub mod corpus;
+pub mod export;
+pub mod queens;
+pub mod schedule;
+pub mod seeds;
+pub mod sudoku;
+pub mod tournament;
+pub mod ui;
@@ -1,62 +1,437 @@
-use rand::{rngs::StdRng, seq::SliceRandom, Rng};
-use std::collections::HashSet;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::{
+    str::FromStr,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use crate::schedule::CoolingSchedule;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct QueenRun {
     pub state: [u8; 8],
     pub steps: usize,
+    pub source: SolutionSource,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SolutionSource {
+    /// Found by the annealing sampler.
+    Sampled,
+    /// Filled in by the deterministic backtracking enumerator.
+    Exhaustive,
+    /// Carried over from an earlier collection passed to `resume_collection`.
+    Resumed,
 }
 
+/// Per-restart step budget of `QueensConfig::default()` and the CLI's `--max-steps`.
+pub const DEFAULT_MAX_STEPS: usize = 100_000;
+pub const DEFAULT_START_TEMP: f64 = 2.4;
+pub const DEFAULT_COOLING_RATE: f64 = 0.995;
+pub const DEFAULT_TEMPERATURE_FLOOR: f64 = 0.25;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct QueensConfig {
     pub max_steps: usize,
-    pub start_temp: f64,
-    pub cooling_rate: f64,
+    /// Wall-clock cap for the whole collection; checked between restarts.
+    pub max_total_duration: Option<Duration>,
+    /// Top up a short collection from the backtracking enumerator.
+    pub exhaustive_fallback: bool,
+    /// After this many accepted uphill moves in a row, the next uphill move is rejected.
+    pub max_consecutive_uphill: Option<usize>,
+    /// Probability of accepting a move that leaves the energy unchanged (1.0 always accepts).
+    pub lateral_accept_prob: f64,
+    /// Lowest probability of accepting an uphill move, however large its `delta / T`.
+    pub min_accept_prob: f64,
+    /// Cooling never takes the temperature below this value.
+    pub temperature_floor: f64,
+    /// How the temperature falls within each restart. Queens does not track acceptance
+    /// ratios, so an `Adaptive` cooling steps like `Geometric`; the CLI refuses it.
+    pub schedule: CoolingSchedule,
+    /// Keep at most this many runs in `CollectionResult::runs`; later unique solutions are
+    /// still counted in `found` but not stored.
+    pub max_store: Option<usize>,
+    /// Start each restart from the one of `DIVERSE_CANDIDATES` random placements that lies
+    /// farthest from the solutions found so far, instead of from a single random placement.
+    pub diverse_restarts: bool,
+    /// Pick the row to move with probability proportional to its queen's attacks (see
+    /// `pick_attacked_row`) instead of uniformly.
+    pub target_worst: bool,
 }
 
+impl Default for QueensConfig {
+    /// The configuration `queens` runs with when no sampler flags are given.
+    fn default() -> Self {
+        QueensConfig {
+            max_steps: DEFAULT_MAX_STEPS,
+            max_total_duration: None,
+            exhaustive_fallback: false,
+            max_consecutive_uphill: None,
+            lateral_accept_prob: 1.0,
+            min_accept_prob: 0.0,
+            temperature_floor: DEFAULT_TEMPERATURE_FLOOR,
+            schedule: CoolingSchedule::geometric(DEFAULT_START_TEMP, DEFAULT_COOLING_RATE),
+            max_store: None,
+            diverse_restarts: false,
+            target_worst: false,
+        }
+    }
+}
+
+/// Random placements drawn per restart under `QueensConfig::diverse_restarts`.
+pub const DIVERSE_CANDIDATES: usize = 8;
+
+#[derive(Default)]
 pub struct CollectionResult {
     pub runs: Vec<QueenRun>,
+    /// Unique solutions found, including any dropped by `max_store`.
+    pub found: usize,
     pub restarts: usize,
     pub total_steps: usize,
+    pub timed_out: bool,
+    /// Earliest step within a restart at which cooling reached `temperature_floor`.
+    pub floor_reached_at: Option<usize>,
+    pub memory: MemoryProfile,
+    /// Unique solutions found but discarded because they failed the filter.
+    pub filtered_out: usize,
+}
+
+/// What a collection kept in memory at its peak, counted as elements times element size.
+/// A rough guide for sizing `--all-solutions` runs, not an allocator measurement.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryProfile {
+    /// Most runs stored at once; runs are never removed, so this is the final `runs.len()`.
+    pub peak_stored: usize,
+    /// Solutions marked as seen, including those dropped under `max_store`.
+    pub peak_tracked: usize,
+    /// Allocated capacity of the run list plus the fixed-size `SolutionSet`, in bytes.
+    pub estimated_bytes: usize,
+}
+
+impl MemoryProfile {
+    fn measure(runs: &Vec<QueenRun>, unique: &SolutionSet) -> Self {
+        MemoryProfile {
+            peak_stored: runs.len(),
+            peak_tracked: unique.len(),
+            estimated_bytes: runs.capacity() * size_of::<QueenRun>() + size_of::<SolutionSet>(),
+        }
+    }
+}
+
+/// The 92 solutions in ascending order, which gives each a fixed index.
+fn solution_table() -> &'static [[u8; 8]] {
+    static TABLE: OnceLock<Vec<[u8; 8]>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut solutions = enumerate_all();
+        solutions.sort();
+        solutions
+    })
+}
+
+/// True if `state` is one of the 92 solutions.
+pub fn is_solution(state: &[u8; 8]) -> bool {
+    solution_table().binary_search(state).is_ok()
+}
+
+/// A set of 8-Queens solutions, one bit per solution: 16 bytes however many are found, so a
+/// collection under `max_store` stays bounded while still counting every unique solution.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SolutionSet(u128);
+
+impl SolutionSet {
+    /// Adds `state`, returning true if it was not already present. Placements that are not
+    /// solutions cannot be tracked and are never added.
+    pub fn insert(&mut self, state: [u8; 8]) -> bool {
+        let Ok(index) = solution_table().binary_search(&state) else {
+            return false;
+        };
+        let bit = 1u128 << index;
+        let added = self.0 & bit == 0;
+        self.0 |= bit;
+        added
+    }
+
+    pub fn contains(&self, state: &[u8; 8]) -> bool {
+        solution_table()
+            .binary_search(state)
+            .is_ok_and(|index| self.0 & (1u128 << index) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The solutions in the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = [u8; 8]> + '_ {
+        solution_table()
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| self.0 & (1u128 << index) != 0)
+            .map(|(_, &state)| state)
+    }
+}
+
+impl FromIterator<[u8; 8]> for SolutionSet {
+    fn from_iter<I: IntoIterator<Item = [u8; 8]>>(states: I) -> Self {
+        let mut set = SolutionSet::default();
+        for state in states {
+            set.insert(state);
+        }
+        set
+    }
+}
+
+/// Number of distinct 8-Queens solutions.
+pub const TOTAL_SOLUTIONS: usize = 92;
+
+/// Called after every restart with `(restarts_done, unique_found, target)`.
+pub type ProgressFn<'a> = &'a mut dyn FnMut(usize, usize, usize);
+
+/// Decides whether a solution is kept; see `resume_collection_filtered`.
+pub type FilterFn<'a> = &'a dyn Fn(&[u8; 8]) -> bool;
+
+/// Named constraints for `--filter`. Squares use chess notation on the board as printed:
+/// files `a`-`h` are columns left to right and ranks `8`-`1` are rows top to bottom.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SolutionFilter {
+    /// No queen on a1, a8, h1 or h8.
+    NoCorner,
+    /// A queen on the square in row `row` (0 is rank 8) and column `col` (0 is file a).
+    QueenAt { row: u8, col: u8 },
+}
+
+impl SolutionFilter {
+    pub fn matches(&self, state: &[u8; 8]) -> bool {
+        match *self {
+            SolutionFilter::NoCorner => {
+                [state[0], state[7]].iter().all(|&col| col != 0 && col != 7)
+            }
+            SolutionFilter::QueenAt { row, col } => state[row as usize] == col,
+        }
+    }
+}
+
+impl FromStr for SolutionFilter {
+    type Err = String;
+
+    /// `no-corner` or `queen-at:SQUARE`, e.g. `queen-at:d4`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let lower = text.to_ascii_lowercase();
+        if lower == "no-corner" {
+            return Ok(SolutionFilter::NoCorner);
+        }
+        let square = lower
+            .strip_prefix("queen-at:")
+            .ok_or_else(|| format!("unknown filter {text:?} (no-corner, queen-at:SQUARE)"))?;
+        match square.as_bytes() {
+            &[file @ b'a'..=b'h', rank @ b'1'..=b'8'] => Ok(SolutionFilter::QueenAt {
+                row: b'8' - rank,
+                col: file - b'a',
+            }),
+            _ => Err(format!("{square:?} is not a square from a1 to h8")),
+        }
+    }
+}
+
+/// How `queens` prints each solution.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BoardFormat {
+    /// The 8×8 grid.
+    #[default]
+    Grid,
+    /// The column vector on one line, e.g. `04752613`.
+    Compact,
+    /// `to_fen_like` notation.
+    Fen,
+}
+
+impl FromStr for BoardFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "grid" => Ok(BoardFormat::Grid),
+            "compact" => Ok(BoardFormat::Compact),
+            "fen" => Ok(BoardFormat::Fen),
+            _ => Err(format!("unknown format {text:?} (grid, compact, fen)")),
+        }
+    }
+}
+
+pub fn collect_solutions<R: Rng + ?Sized>(
+    target: usize,
+    max_restarts: usize,
+    config: &QueensConfig,
+    rng: &mut R,
+    progress: Option<ProgressFn>,
+) -> CollectionResult {
+    resume_collection(CollectionResult::default(), target, max_restarts, config, rng, progress)
+}
+
+/// Continues `prior` until it holds `target` unique solutions. Its runs are kept and never
+/// rediscovered, and its restart and step counters carry on; `max_restarts` and the time cap
+/// apply to this call only. Solutions `prior` counted but dropped under `max_store` are
+/// unknown here, so finding one again counts it twice.
+pub fn resume_collection<R: Rng + ?Sized>(
+    prior: CollectionResult,
+    target: usize,
+    max_restarts: usize,
+    config: &QueensConfig,
+    rng: &mut R,
+    progress: Option<ProgressFn>,
+) -> CollectionResult {
+    resume_collection_filtered(prior, target, max_restarts, config, rng, progress, &|_| true)
 }
 
-pub fn collect_solutions(
+/// `resume_collection` that keeps only solutions passing `keep`, e.g. to enumerate the
+/// placements with no queen in a corner. Rejected solutions still cost their restarts and
+/// are counted in `filtered_out`, and `target` counts kept solutions only. `prior`'s runs are
+/// carried over without being checked, and a solution it rejected may be counted again.
+pub fn resume_collection_filtered<R: Rng + ?Sized>(
+    prior: CollectionResult,
     target: usize,
     max_restarts: usize,
     config: &QueensConfig,
-    rng: &mut StdRng,
+    rng: &mut R,
+    mut progress: Option<ProgressFn>,
+    keep: FilterFn,
 ) -> CollectionResult {
-    let mut unique = HashSet::new();
-    let mut runs = Vec::new();
-    let mut restarts = 0;
-    let mut total_steps = 0;
+    let mut rejected = SolutionSet::default();
+    let mut unique: SolutionSet = prior.runs.iter().map(|run| run.state).collect();
+    let dropped = prior.found.saturating_sub(unique.len());
+    let mut runs = prior.runs;
+    let mut restarts = prior.restarts;
+    let mut total_steps = prior.total_steps;
+    let mut timed_out = false;
+    let mut floor_reached_at = prior.floor_reached_at;
+    let started = Instant::now();
+    let first_restart = restarts;
+    let has_room = |runs: &Vec<QueenRun>| config.max_store.is_none_or(|cap| runs.len() < cap);
+    let target = target.saturating_sub(dropped);
 
-    while unique.len() < target && restarts < max_restarts {
+    while unique.len() < target && restarts - first_restart < max_restarts {
+        if config
+            .max_total_duration
+            .is_some_and(|limit| started.elapsed() >= limit)
+        {
+            timed_out = true;
+            break;
+        }
         restarts += 1;
-        if let Some(run) = solve_single(config, rng) {
+        let start = if config.diverse_restarts {
+            farthest_start(&unique, rng)
+        } else {
+            random_queen_state(rng)
+        };
+        let (run, floor_step) = solve_single(start, config, rng);
+        if let Some(step) = floor_step {
+            floor_reached_at = Some(floor_reached_at.map_or(step, |earliest| earliest.min(step)));
+        }
+        if let Some(run) = run {
             total_steps += run.steps;
-            if unique.insert(run.state) {
+            if !keep(&run.state) {
+                rejected.insert(run.state);
+            } else if unique.insert(run.state) && has_room(&runs) {
                 runs.push(run);
             }
         }
+        if let Some(report) = progress.as_mut() {
+            report(restarts, dropped + unique.len(), dropped + target);
+        }
+    }
+
+    if config.exhaustive_fallback {
+        for state in enumerate_all() {
+            if unique.len() >= target {
+                break;
+            }
+            if !keep(&state) {
+                rejected.insert(state);
+            } else if unique.insert(state) && has_room(&runs) {
+                runs.push(QueenRun {
+                    state,
+                    steps: 0,
+                    source: SolutionSource::Exhaustive,
+                });
+            }
+        }
     }
 
+    let memory = MemoryProfile::measure(&runs, &unique);
     CollectionResult {
         runs,
+        found: dropped + unique.len(),
         restarts,
         total_steps,
+        timed_out,
+        floor_reached_at,
+        memory,
+        filtered_out: prior.filtered_out + rejected.len(),
     }
 }
 
-fn solve_single(config: &QueensConfig, rng: &mut StdRng) -> Option<QueenRun> {
-    let mut state = random_queen_state(rng);
+/// Expected restarts still needed to reach `target` unique solutions, from a coupon-collector
+/// model: each restart succeeds with some probability and lands on a uniformly random
+/// solution, so every new solution takes longer to find than the last. The success
+/// probability is fitted to the observed `(restarts, found)` pair. `None` until a solution
+/// has been found.
+pub fn estimate_remaining_restarts(restarts: usize, found: usize, target: usize) -> Option<f64> {
+    let total = TOTAL_SOLUTIONS as f64;
+    let target = target.min(TOTAL_SOLUTIONS);
+    if found >= target {
+        return Some(0.0);
+    }
+    if found == 0 || restarts == 0 {
+        return None;
+    }
+    let unseen_fraction = 1.0 - found as f64 / total;
+    let success = (total * (1.0 - unseen_fraction.powf(1.0 / restarts as f64))).clamp(1e-9, 1.0);
+    let remaining = (found..target)
+        .map(|seen| total / (success * (total - seen as f64)))
+        .sum();
+    Some(remaining)
+}
+
+/// Returns the first valid placement found within `max_restarts`, skipping deduplication.
+pub fn first_solution<R: Rng + ?Sized>(
+    config: &QueensConfig,
+    max_restarts: usize,
+    rng: &mut R,
+) -> Option<QueenRun> {
+    (0..max_restarts).find_map(|_| solve_single(random_queen_state(rng), config, rng).0)
+}
+
+/// One annealing restart; also returns the step at which cooling reached the floor.
+fn solve_single<R: Rng + ?Sized>(
+    start: [u8; 8],
+    config: &QueensConfig,
+    rng: &mut R,
+) -> (Option<QueenRun>, Option<usize>) {
+    let mut state = start;
     let mut energy = queen_conflict_count(&state);
-    let mut temperature = config.start_temp;
+    let mut temperature = config.schedule.initial_temp();
+    let mut uphill_run = 0;
+    let mut floor_reached_at = None;
 
     for step in 0..config.max_steps {
         if energy == 0 {
-            return Some(QueenRun { state, steps: step });
+            let run = QueenRun {
+                state,
+                steps: step,
+                source: SolutionSource::Sampled,
+            };
+            return (Some(run), floor_reached_at);
         }
-        let row = rng.random_range(0..8);
+        let row = if config.target_worst {
+            pick_attacked_row(&state, rng)
+        } else {
+            rng.random_range(0..8)
+        };
         let current = state[row];
         let mut candidate = rng.random_range(0..8);
         while candidate == current {
@@ -65,23 +440,244 @@ fn solve_single(config: &QueensConfig, rng: &mut StdRng) -> Option<QueenRun> {
         state[row] = candidate;
         let new_energy = queen_conflict_count(&state);
         let delta = new_energy as i64 - energy as i64;
-        let accept = if delta <= 0 {
+        let accept = if delta < 0 {
             true
+        } else if delta == 0 {
+            accept_lateral(config.lateral_accept_prob, rng)
+        } else if config
+            .max_consecutive_uphill
+            .is_some_and(|cap| uphill_run >= cap)
+        {
+            uphill_run = 0;
+            false
         } else {
-            let probability = (-(delta as f64) / temperature).exp().min(1.0);
+            let probability = (-(delta as f64) / temperature)
+                .exp()
+                .max(config.min_accept_prob)
+                .min(1.0);
             rng.random_bool(probability)
         };
         if accept {
+            uphill_run = if delta > 0 { uphill_run + 1 } else { 0 };
             energy = new_energy;
         } else {
             state[row] = current;
         }
-        temperature = (temperature * config.cooling_rate).max(0.25);
+        temperature = config
+            .schedule
+            .next_temp(step + 1, temperature)
+            .max(config.temperature_floor);
+        if floor_reached_at.is_none() && temperature <= config.temperature_floor {
+            floor_reached_at = Some(step + 1);
+        }
+    }
+    (None, floor_reached_at)
+}
+
+/// Greedy max-min selection: starting from the first run, repeatedly adds the run whose
+/// smallest Hamming distance to the runs already chosen is largest. Ties keep pool order.
+pub fn select_diverse(pool: &[QueenRun], k: usize) -> Vec<QueenRun> {
+    let mut selected: Vec<QueenRun> = pool.iter().take(1.min(k)).copied().collect();
+    let mut remaining: Vec<QueenRun> = pool.iter().skip(1).copied().collect();
+    while selected.len() < k && !remaining.is_empty() {
+        let mut best_index = 0;
+        let mut best_distance = 0;
+        for (index, candidate) in remaining.iter().enumerate() {
+            let distance = selected
+                .iter()
+                .map(|chosen| state_distance(&chosen.state, &candidate.state))
+                .min()
+                .unwrap_or(0);
+            if distance > best_distance {
+                best_index = index;
+                best_distance = distance;
+            }
+        }
+        selected.push(remaining.remove(best_index));
+    }
+    selected
+}
+
+/// Hamming distance between two placements: the rows whose queen sits in a different column.
+pub fn state_distance(a: &[u8; 8], b: &[u8; 8]) -> usize {
+    a.iter().zip(b).filter(|(x, y)| x != y).count()
+}
+
+/// The placement in a FEN-like notation for chess tooling: ranks 8 to 1 (rows top to bottom)
+/// separated by `/`, a `Q` for the queen and digits for runs of empty squares, e.g.
+/// `Q7/4Q3/7Q/5Q2/2Q5/6Q1/1Q6/3Q4` for `04752613`.
+pub fn to_fen_like(state: &[u8; 8]) -> String {
+    let ranks: Vec<String> = state
+        .iter()
+        .map(|&col| {
+            let (before, after) = (col, 7 - col);
+            let mut rank = String::new();
+            if before > 0 {
+                rank.push(char::from(b'0' + before));
+            }
+            rank.push('Q');
+            if after > 0 {
+                rank.push(char::from(b'0' + after));
+            }
+            rank
+        })
+        .collect();
+    ranks.join("/")
+}
+
+/// Parses `to_fen_like` output. Every rank must cover eight squares and hold exactly one
+/// queen (`Q` or `q`), since a placement has one queen per row.
+pub fn from_fen_like(text: &str) -> Result<[u8; 8], String> {
+    let ranks: Vec<&str> = text.trim().split('/').collect();
+    if ranks.len() != 8 {
+        return Err(format!("expected 8 ranks, found {}", ranks.len()));
+    }
+    let mut state = [0u8; 8];
+    for (row, rank) in ranks.iter().enumerate() {
+        let label = 8 - row;
+        let mut squares = 0u32;
+        let mut queen = None;
+        for symbol in rank.chars() {
+            match symbol {
+                '1'..='8' => squares += symbol.to_digit(10).unwrap_or_default(),
+                'Q' | 'q' if queen.is_none() => {
+                    queen = Some(squares as u8);
+                    squares += 1;
+                }
+                'Q' | 'q' => return Err(format!("rank {label} has more than one queen")),
+                _ => return Err(format!("rank {label}: unexpected {symbol:?}")),
+            }
+        }
+        if squares != 8 {
+            return Err(format!("rank {label} covers {squares} squares, not 8"));
+        }
+        state[row] = queen.ok_or_else(|| format!("rank {label} has no queen"))?;
+    }
+    Ok(state)
+}
+
+/// Plateau moves are always taken at probability 1.0 without drawing from the RNG.
+fn accept_lateral<R: Rng + ?Sized>(probability: f64, rng: &mut R) -> bool {
+    probability >= 1.0 || (probability > 0.0 && rng.random_bool(probability))
+}
+
+/// Every conflict-free 8-Queens placement, in lexicographic order.
+pub fn enumerate_all() -> Vec<[u8; 8]> {
+    let mut solutions = Vec::new();
+    for_each_solution(8, |columns| {
+        let mut state = [0u8; 8];
+        state.copy_from_slice(columns);
+        solutions.push(state);
+    });
+    solutions
+}
+
+/// Depth-first backtracking over an `n`×`n` board, visiting each placement's column
+/// vector (one queen per row) in lexicographic order.
+pub fn for_each_solution(n: usize, mut visit: impl FnMut(&[u8])) {
+    let mut columns = Vec::with_capacity(n);
+    place_queen(n, &mut columns, &mut visit);
+}
+
+/// Exact number of `n`×`n` placements for which `predicate` holds, by full enumeration; the
+/// ground truth that a filtered `resume_collection_filtered` run can only approach.
+pub fn count_solutions_matching(n: usize, predicate: impl Fn(&[u8]) -> bool) -> usize {
+    let mut count = 0;
+    for_each_solution(n, |columns| count += usize::from(predicate(columns)));
+    count
+}
+
+fn place_queen(n: usize, columns: &mut Vec<u8>, visit: &mut impl FnMut(&[u8])) {
+    if columns.len() == n {
+        visit(columns);
+        return;
+    }
+    let row = columns.len();
+    for col in 0..n {
+        let safe = columns.iter().enumerate().all(|(other_row, &other_col)| {
+            let other_col = other_col as usize;
+            other_col != col && other_col.abs_diff(col) != row - other_row
+        });
+        if safe {
+            columns.push(col as u8);
+            place_queen(n, columns, visit);
+            columns.pop();
+        }
+    }
+}
+
+type CellMap = fn(usize, usize) -> (usize, usize);
+
+/// The eight symmetries of the square, as maps of a queen's `(row, col)`.
+const BOARD_SYMMETRIES: [CellMap; 8] = [
+    |row, col| (row, col),
+    |row, col| (col, 7 - row),
+    |row, col| (7 - row, 7 - col),
+    |row, col| (7 - col, row),
+    |row, col| (row, 7 - col),
+    |row, col| (7 - row, col),
+    |row, col| (col, row),
+    |row, col| (7 - col, 7 - row),
+];
+
+/// Lexicographically smallest image of `state` under rotations and reflections, shared by
+/// every member of its symmetry class.
+pub fn canonical_form(state: &[u8; 8]) -> [u8; 8] {
+    BOARD_SYMMETRIES
+        .iter()
+        .map(|transform| {
+            let mut image = [0u8; 8];
+            for (row, &col) in state.iter().enumerate() {
+                let (new_row, new_col) = transform(row, col as usize);
+                image[new_row] = new_col as u8;
+            }
+            image
+        })
+        .min()
+        .unwrap_or(*state)
+}
+
+/// The 12 symmetry classes of the 92 solutions as `(canonical representative, size)`, in
+/// representative order. Eleven classes have 8 members; the one that is symmetric under a
+/// half turn has 4.
+pub fn symmetry_classes() -> Vec<([u8; 8], usize)> {
+    let mut classes: Vec<([u8; 8], usize)> = Vec::new();
+    for state in enumerate_all() {
+        let canonical = canonical_form(&state);
+        match classes.iter_mut().find(|(representative, _)| *representative == canonical) {
+            Some((_, size)) => *size += 1,
+            None => classes.push((canonical, 1)),
+        }
     }
-    None
+    classes.sort();
+    classes
 }
 
-fn random_queen_state(rng: &mut StdRng) -> [u8; 8] {
+/// The random placement, out of `DIVERSE_CANDIDATES`, whose nearest known solution is
+/// farthest away; ties keep the earlier draw. Every candidate is drawn even when `found` is
+/// empty, so the RNG stream does not depend on what has been found.
+fn farthest_start<R: Rng + ?Sized>(found: &SolutionSet, rng: &mut R) -> [u8; 8] {
+    let nearest = |state: &[u8; 8]| {
+        found
+            .iter()
+            .map(|solution| state_distance(&solution, state))
+            .min()
+            .unwrap_or(0)
+    };
+    let mut best = random_queen_state(rng);
+    let mut best_distance = nearest(&best);
+    for _ in 1..DIVERSE_CANDIDATES {
+        let candidate = random_queen_state(rng);
+        let distance = nearest(&candidate);
+        if distance > best_distance {
+            best = candidate;
+            best_distance = distance;
+        }
+    }
+    best
+}
+
+fn random_queen_state<R: Rng + ?Sized>(rng: &mut R) -> [u8; 8] {
     let mut columns: Vec<u8> = (0..8).map(|value| value as u8).collect();
     columns.shuffle(rng);
     let mut state = [0u8; 8];
@@ -106,16 +702,347 @@ fn queen_conflict_count(state: &[u8; 8]) -> usize {
 }
 
 pub fn conflict_mask(state: &[u8; 8]) -> [bool; 8] {
-    let mut mask = [false; 8];
+    conflict_counts(state).map(|attacks| attacks > 0)
+}
+
+/// For each row, how many other queens attack its queen.
+pub fn conflict_counts(state: &[u8; 8]) -> [usize; 8] {
+    let mut counts = [0; 8];
     for i in 0..8 {
         for j in (i + 1)..8 {
             if state[i] == state[j]
                 || (state[i] as i16 - state[j] as i16).abs() == (i as i16 - j as i16).abs()
             {
-                mask[i] = true;
-                mask[j] = true;
+                counts[i] += 1;
+                counts[j] += 1;
             }
         }
     }
-    mask
+    counts
+}
+
+/// A row drawn with probability proportional to its queen's attacks, so the most attacked
+/// queen moves most often; uniform when nothing is attacked.
+pub fn pick_attacked_row<R: Rng + ?Sized>(state: &[u8; 8], rng: &mut R) -> usize {
+    let counts = conflict_counts(state);
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return rng.random_range(0..8);
+    }
+    let mut pick = rng.random_range(0..total);
+    for (row, &count) in counts.iter().enumerate() {
+        if pick < count {
+            return row;
+        }
+        pick -= count;
+    }
+    unreachable!("pick is below the total of the counts")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn total_time_cap_returns_partial_results() {
+        let config = QueensConfig {
+            max_total_duration: Some(Duration::ZERO),
+            ..QueensConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        let result = collect_solutions(TOTAL_SOLUTIONS, usize::MAX, &config, &mut rng, None);
+        assert!(result.timed_out);
+        assert_eq!((result.found, result.restarts), (0, 0));
+
+        let config = QueensConfig {
+            max_total_duration: Some(Duration::from_millis(5)),
+            ..QueensConfig::default()
+        };
+        let started = Instant::now();
+        let result = collect_solutions(TOTAL_SOLUTIONS, usize::MAX, &config, &mut rng, None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(result.timed_out || result.found == TOTAL_SOLUTIONS);
+        assert_eq!(result.runs.len(), result.found);
+    }
+
+    #[test]
+    fn first_solution_is_valid_or_none_when_starved() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let run = first_solution(&QueensConfig::default(), 50, &mut rng).unwrap();
+        assert_eq!(queen_conflict_count(&run.state), 0);
+        assert!(enumerate_all().contains(&run.state));
+
+        let starved = QueensConfig {
+            max_steps: 1,
+            ..QueensConfig::default()
+        };
+        assert!(first_solution(&starved, 1, &mut rng).is_none());
+        assert!(first_solution(&QueensConfig::default(), 0, &mut rng).is_none());
+    }
+
+    #[test]
+    fn progress_ends_with_the_number_of_runs_collected() {
+        let mut calls = Vec::new();
+        let mut record = |restarts, found, target| calls.push((restarts, found, target));
+        let mut rng = StdRng::seed_from_u64(3);
+        let config = QueensConfig::default();
+        let result = collect_solutions(10, 200, &config, &mut rng, Some(&mut record));
+        assert_eq!(calls.len(), result.restarts);
+        assert_eq!(calls.last(), Some(&(result.restarts, result.runs.len(), 10)));
+    }
+
+    #[test]
+    fn exhaustive_fallback_completes_a_starved_collection() {
+        let config = QueensConfig {
+            exhaustive_fallback: true,
+            ..QueensConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(4);
+        let result = collect_solutions(TOTAL_SOLUTIONS, 3, &config, &mut rng, None);
+        assert_eq!(result.restarts, 3);
+        assert_eq!(result.found, TOTAL_SOLUTIONS);
+        let unique: HashSet<[u8; 8]> = result.runs.iter().map(|run| run.state).collect();
+        assert_eq!(unique.len(), TOTAL_SOLUTIONS);
+        assert!(result.runs.iter().any(|run| run.source == SolutionSource::Exhaustive));
+    }
+
+    fn run(state: [u8; 8]) -> QueenRun {
+        QueenRun {
+            state,
+            steps: 0,
+            source: SolutionSource::Sampled,
+        }
+    }
+
+    #[test]
+    fn diverse_selection_skips_near_duplicates() {
+        let first = [0, 4, 7, 5, 2, 6, 1, 3];
+        let near = [4, 0, 7, 5, 2, 6, 1, 3];
+        let far = [7, 1, 3, 0, 6, 4, 2, 5];
+        assert_eq!(state_distance(&first, &near), 2);
+        let selected = select_diverse(&[run(first), run(near), run(far)], 2);
+        let states: Vec<[u8; 8]> = selected.iter().map(|run| run.state).collect();
+        assert_eq!(states, vec![first, far]);
+        assert_eq!(select_diverse(&[run(first), run(near)], 5).len(), 2);
+    }
+
+    #[test]
+    fn remaining_restart_estimate_shrinks_as_solutions_are_found() {
+        assert_eq!(estimate_remaining_restarts(5, 0, TOTAL_SOLUTIONS), None);
+        assert_eq!(estimate_remaining_restarts(500, TOTAL_SOLUTIONS, TOTAL_SOLUTIONS), Some(0.0));
+        let progress = [(10, 9), (40, 33), (120, 66), (300, 88)];
+        let estimates: Vec<f64> = progress
+            .iter()
+            .map(|&(restarts, found)| {
+                estimate_remaining_restarts(restarts, found, TOTAL_SOLUTIONS).unwrap()
+            })
+            .collect();
+        assert!(estimates.windows(2).all(|pair| pair[1] < pair[0]), "{estimates:?}");
+        let fewer = estimate_remaining_restarts(100, 40, TOTAL_SOLUTIONS).unwrap();
+        let more = estimate_remaining_restarts(100, 60, TOTAL_SOLUTIONS).unwrap();
+        assert!(more < fewer);
+    }
+
+    #[test]
+    fn symmetry_classes_partition_the_solutions() {
+        let classes = symmetry_classes();
+        assert_eq!(classes.len(), 12);
+        assert_eq!(classes.iter().map(|(_, size)| size).sum::<usize>(), TOTAL_SOLUTIONS);
+        let small: Vec<&[u8; 8]> = classes
+            .iter()
+            .filter(|(_, size)| *size != 8)
+            .map(|(representative, _)| representative)
+            .collect();
+        assert_eq!(small, [&[2, 4, 1, 7, 0, 6, 3, 5]]);
+        let half_turn: Vec<u8> = (0..8).map(|row| 7 - small[0][7 - row]).collect();
+        assert_eq!(half_turn, small[0]);
+        let representatives: Vec<[u8; 8]> = classes.iter().map(|(state, _)| *state).collect();
+        for state in enumerate_all() {
+            assert!(representatives.contains(&canonical_form(&state)));
+        }
+    }
+
+    #[test]
+    fn max_store_caps_retained_runs_but_not_the_count() {
+        let config = QueensConfig {
+            max_store: Some(5),
+            exhaustive_fallback: true,
+            ..QueensConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(5);
+        let result = collect_solutions(20, 50, &config, &mut rng, None);
+        assert_eq!(result.runs.len(), 5);
+        assert_eq!(result.found, 20);
+        assert_eq!(result.memory.peak_stored, 5);
+        assert_eq!(result.memory.peak_tracked, 20);
+
+        // Finding every solution retains no more than finding 20 did.
+        let all = collect_solutions(TOTAL_SOLUTIONS, 50, &config, &mut rng, None);
+        assert_eq!((all.found, all.runs.len()), (TOTAL_SOLUTIONS, 5));
+        assert_eq!(all.memory.estimated_bytes, result.memory.estimated_bytes);
+    }
+
+    #[test]
+    fn solution_set_tracks_each_solution_once() {
+        let solutions = enumerate_all();
+        let mut set = SolutionSet::default();
+        assert!(set.is_empty());
+        assert!(set.insert(solutions[40]) && set.insert(solutions[3]));
+        assert!(!set.insert(solutions[40]));
+        assert!(!set.insert([0, 1, 2, 3, 4, 5, 6, 7]));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&solutions[3]) && !set.contains(&solutions[4]));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [solutions[3], solutions[40]]);
+        let all: SolutionSet = solutions.iter().copied().collect();
+        assert_eq!(all.len(), TOTAL_SOLUTIONS);
+        assert!(solutions.iter().all(is_solution));
+        assert!(!is_solution(&[0, 4, 7, 5, 2, 6, 3, 1]));
+    }
+
+    #[test]
+    fn resuming_keeps_the_known_solutions_and_accumulates_counters() {
+        let config = QueensConfig::default();
+        let mut rng = StdRng::seed_from_u64(16);
+        let prior = collect_solutions(5, 100, &config, &mut rng, None);
+        let known: Vec<[u8; 8]> = prior.runs.iter().map(|run| run.state).collect();
+        let (restarts, steps) = (prior.restarts, prior.total_steps);
+        assert_eq!(known.len(), 5);
+
+        let resumed = resume_collection(prior, 10, 200, &config, &mut rng, None);
+        assert_eq!((resumed.found, resumed.runs.len()), (10, 10));
+        let states: Vec<[u8; 8]> = resumed.runs.iter().map(|run| run.state).collect();
+        assert_eq!(states[..5], known[..]);
+        assert_eq!(states.iter().collect::<HashSet<_>>().len(), 10);
+        assert!(resumed.restarts > restarts && resumed.total_steps > steps);
+    }
+
+    #[test]
+    fn memory_profile_counts_the_stored_runs() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let result = collect_solutions(12, 200, &QueensConfig::default(), &mut rng, None);
+        assert_eq!(result.memory.peak_stored, result.runs.len());
+        assert_eq!(result.memory.peak_tracked, result.found);
+        let run_bytes = result.runs.len() * size_of::<QueenRun>();
+        assert!(result.memory.estimated_bytes >= run_bytes);
+    }
+
+    #[test]
+    fn state_distance_is_a_symmetric_row_count() {
+        let (a, b) = ([0, 4, 7, 5, 2, 6, 1, 3], [7, 1, 3, 0, 6, 4, 2, 5]);
+        assert_eq!(state_distance(&a, &a), 0);
+        assert_eq!(state_distance(&a, &b), state_distance(&b, &a));
+        assert_eq!(state_distance(&a, &[0, 4, 7, 5, 2, 6, 3, 1]), 2);
+    }
+
+    #[test]
+    fn no_corner_filter_keeps_only_corner_free_solutions() {
+        let filter = SolutionFilter::NoCorner;
+        let keep = |state: &[u8; 8]| filter.matches(state);
+        let allowed = enumerate_all().iter().filter(|state| keep(state)).count();
+        assert!(allowed < TOTAL_SOLUTIONS);
+        let config = QueensConfig {
+            exhaustive_fallback: true,
+            ..QueensConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(24);
+        let prior = CollectionResult::default();
+        let result =
+            resume_collection_filtered(prior, TOTAL_SOLUTIONS, 50, &config, &mut rng, None, &keep);
+        assert_eq!(result.runs.len(), allowed);
+        assert_eq!(result.filtered_out, TOTAL_SOLUTIONS - allowed);
+        for run in &result.runs {
+            let corners = [(0, 0), (0, 7), (7, 0), (7, 7)];
+            assert!(corners.iter().all(|&(row, col)| run.state[row] != col));
+        }
+    }
+
+    #[test]
+    fn filters_parse_squares_in_chess_notation() {
+        let d4 = "queen-at:d4".parse::<SolutionFilter>().unwrap();
+        assert_eq!(d4, SolutionFilter::QueenAt { row: 4, col: 3 });
+        assert!(d4.matches(&[0, 4, 7, 5, 3, 6, 1, 2]));
+        assert_eq!("No-Corner".parse(), Ok(SolutionFilter::NoCorner));
+        assert!("queen-at:i9".parse::<SolutionFilter>().is_err());
+        assert!("corner".parse::<SolutionFilter>().is_err());
+    }
+
+    #[test]
+    fn diverse_start_is_the_farthest_of_its_candidates() {
+        // Whether this finds the 92 in fewer restarts depends on the seeds (see the README),
+        // so only the choice of start is pinned down here.
+        let found: SolutionSet = enumerate_all().into_iter().take(10).collect();
+        let nearest = |state: &[u8; 8]| {
+            found.iter().map(|solution| state_distance(&solution, state)).min().unwrap()
+        };
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut replay = rng.clone();
+            let start = farthest_start(&found, &mut rng);
+            let candidates: Vec<[u8; 8]> =
+                (0..DIVERSE_CANDIDATES).map(|_| random_queen_state(&mut replay)).collect();
+            let farthest = candidates.iter().map(nearest).max().unwrap();
+            let first_farthest = candidates.iter().find(|state| nearest(state) == farthest);
+            assert_eq!(Some(&start), first_farthest);
+            // All candidates are drawn, so the stream continues in step with the replay.
+            assert_eq!(rng.random::<u64>(), replay.random::<u64>());
+
+            let mut rng = StdRng::seed_from_u64(seed);
+            assert_eq!(farthest_start(&SolutionSet::default(), &mut rng), candidates[0]);
+        }
+    }
+
+    #[test]
+    fn fen_like_strings_round_trip() {
+        let known = [0, 4, 7, 5, 2, 6, 1, 3];
+        assert_eq!(to_fen_like(&known), "Q7/4Q3/7Q/5Q2/2Q5/6Q1/1Q6/3Q4");
+        for state in enumerate_all() {
+            assert_eq!(from_fen_like(&to_fen_like(&state)), Ok(state));
+        }
+        assert_eq!(from_fen_like(" q7/4Q3/7Q/5Q2/2Q5/6Q1/1Q6/3Q4\n"), Ok(known));
+    }
+
+    #[test]
+    fn malformed_fen_like_strings_are_rejected() {
+        let err = |text: &str| from_fen_like(text).unwrap_err();
+        assert_eq!(err("Q7/4Q3"), "expected 8 ranks, found 2");
+        assert_eq!(err("8/4Q3/7Q/5Q2/2Q5/6Q1/1Q6/3Q4"), "rank 8 has no queen");
+        assert_eq!(err("QQ6/4Q3/7Q/5Q2/2Q5/6Q1/1Q6/3Q4"), "rank 8 has more than one queen");
+        assert_eq!(err("Q6/4Q3/7Q/5Q2/2Q5/6Q1/1Q6/3Q4"), "rank 8 covers 7 squares, not 8");
+        assert_eq!(err("Q7/4K3/7Q/5Q2/2Q5/6Q1/1Q6/3Q4"), "rank 7: unexpected 'K'");
+    }
+
+    #[test]
+    fn constrained_counts_match_the_known_totals() {
+        assert_eq!(count_solutions_matching(8, |columns| columns[0] == 0), 4);
+        assert_eq!(count_solutions_matching(8, |_| true), 92);
+        // Every solution has a queen somewhere on the a-file.
+        assert_eq!(count_solutions_matching(8, |columns| columns.contains(&0)), 92);
+        assert_eq!(count_solutions_matching(6, |_| true), 4);
+        assert_eq!(count_solutions_matching(4, |columns| columns[0] == 0), 0);
+    }
+
+    #[test]
+    fn most_attacked_queen_is_picked_most_often() {
+        // The solution 04752613 with the first queen moved to column 5, where it attacks
+        // three queens that each attack only it.
+        let state = [5, 4, 7, 5, 2, 6, 1, 3];
+        assert_eq!(conflict_counts(&state), [3, 1, 1, 1, 0, 0, 0, 0]);
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut picks = [0usize; 8];
+        for _ in 0..6000 {
+            picks[pick_attacked_row(&state, &mut rng)] += 1;
+        }
+        assert!((2700..3300).contains(&picks[0]), "{picks:?}");
+        assert!(picks[1..4].iter().all(|count| (800..1200).contains(count)), "{picks:?}");
+        assert!(picks[4..].iter().all(|&count| count == 0), "{picks:?}");
+
+        let solution = [0, 4, 7, 5, 2, 6, 1, 3];
+        let mut unattacked = [0usize; 8];
+        for _ in 0..800 {
+            unattacked[pick_attacked_row(&solution, &mut rng)] += 1;
+        }
+        assert!(unattacked.iter().all(|&count| count > 50), "{unattacked:?}");
+    }
 }
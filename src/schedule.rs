@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use std::{f64::consts::E, fmt, str::FromStr};
+
+/// Proposals the acceptance ratio covers unless `SamplerConfig::acceptance_window` (the CLI's
+/// `--acceptance-window`) says otherwise.
+pub const ACCEPTANCE_WINDOW: usize = 500;
+
+/// A whole annealing curve: the temperature a run starts from and how it cools from there.
+/// The sampler's floor is applied on top of whatever the schedule returns.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CoolingSchedule {
+    pub start_temp: f64,
+    pub cooling: Cooling,
+}
+
+/// How the temperature moves from one step to the next.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Cooling {
+    /// Multiply by `rate` after every step.
+    Geometric { rate: f64 },
+    /// Subtract `step` after every step, stopping at zero.
+    Linear { step: f64 },
+    /// `start_temp * exp(-k * step)`: the geometric curve with rate `exp(-k)`, computed from
+    /// the step count instead of compounded on the previous temperature.
+    Exponential { k: f64 },
+    /// `start_temp / ln(e + step)`: slow, with the classic convergence guarantee.
+    Logarithmic,
+    /// Steer the acceptance ratio over the sampler's acceptance window towards
+    /// `target ± band`: cool by `rate` inside the band, twice as fast above it, and reheat by
+    /// the same factor below it. Geometric until the window has filled.
+    Adaptive { target: f64, band: f64, rate: f64 },
+    /// Step `k` runs at `temperatures[k]`, holding the last value once the array runs out;
+    /// for schedules produced by an external optimizer. `start_temp` is unused.
+    Explicit(Vec<f64>),
+    /// Reverse annealing: start cold at `low`, heat geometrically to `start_temp` after half
+    /// of `steps`, then cool back to `low`. Meant for refining a warm start.
+    Reverse { low: f64, steps: usize },
+}
+
+impl CoolingSchedule {
+    pub fn geometric(start_temp: f64, rate: f64) -> Self {
+        CoolingSchedule {
+            start_temp,
+            cooling: Cooling::Geometric { rate },
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.cooling.name()
+    }
+
+    /// Temperature before the first step: `start_temp`, except where the cooling fixes its
+    /// own starting point.
+    pub fn initial_temp(&self) -> f64 {
+        match &self.cooling {
+            Cooling::Explicit(_) => self.explicit_temp(0).unwrap_or(self.start_temp),
+            Cooling::Reverse { low, .. } => *low,
+            _ => self.start_temp,
+        }
+    }
+
+    /// Temperature after `step` steps, given the temperature before it.
+    pub fn next_temp(&self, step: usize, current: f64) -> f64 {
+        self.next_temp_steered(step, current, None)
+    }
+
+    /// `next_temp` for a sampler that measures its acceptance ratio, the share of recent
+    /// proposals accepted once a full window has been seen. Only `Adaptive` uses it.
+    pub fn next_temp_steered(&self, step: usize, current: f64, ratio: Option<f64>) -> f64 {
+        let start = self.start_temp;
+        match self.cooling {
+            Cooling::Geometric { rate } => current * rate,
+            Cooling::Linear { step: fall } => (current - fall).max(0.0),
+            Cooling::Exponential { k } => start * (-k * step as f64).exp(),
+            Cooling::Logarithmic => start / (E + step as f64).ln(),
+            Cooling::Adaptive { target, band, rate } => match ratio {
+                Some(ratio) if ratio < target - band => current / rate,
+                Some(ratio) if ratio > target + band => current * rate * rate,
+                _ => current * rate,
+            },
+            Cooling::Explicit(_) => self.explicit_temp(step).unwrap_or(current),
+            Cooling::Reverse { low, steps } => {
+                let low = low.max(f64::MIN_POSITIVE);
+                let half = steps.max(2) as f64 / 2.0;
+                let heat = 1.0 - (step as f64 / half - 1.0).abs().min(1.0);
+                low * (start / low).powf(heat)
+            }
+        }
+    }
+
+    /// Temperature of step `step` under an explicit schedule; `None` for computed schedules
+    /// (and for an empty array).
+    pub fn explicit_temp(&self, step: usize) -> Option<f64> {
+        match &self.cooling {
+            Cooling::Explicit(temperatures) => temperatures
+                .get(step)
+                .or(temperatures.last())
+                .copied(),
+            _ => None,
+        }
+    }
+
+    /// The curve to restart on after a reheat with `steps_left` steps of budget remaining:
+    /// `start_temp` (or every explicit temperature) scaled by `factor`. `Linear` falls to zero
+    /// over the remaining steps again, and `Reverse` fits a new cycle into them.
+    pub fn reheated(&self, factor: f64, steps_left: usize) -> CoolingSchedule {
+        let start_temp = self.start_temp * factor;
+        let cooling = match &self.cooling {
+            Cooling::Linear { .. } => Cooling::Linear {
+                step: start_temp / steps_left.max(1) as f64,
+            },
+            Cooling::Explicit(temperatures) => {
+                Cooling::Explicit(temperatures.iter().map(|value| value * factor).collect())
+            }
+            Cooling::Reverse { low, .. } => Cooling::Reverse {
+                low: *low,
+                steps: steps_left,
+            },
+            cooling => cooling.clone(),
+        };
+        CoolingSchedule {
+            start_temp,
+            cooling,
+        }
+    }
+}
+
+impl fmt::Display for CoolingSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Cooling {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cooling::Geometric { .. } => "geometric",
+            Cooling::Linear { .. } => "linear",
+            Cooling::Exponential { .. } => "exponential",
+            Cooling::Logarithmic => "logarithmic",
+            Cooling::Adaptive { .. } => "adaptive",
+            Cooling::Explicit(_) => "explicit",
+            Cooling::Reverse { .. } => "reverse",
+        }
+    }
+}
+
+/// `--schedule` as written: a cooling name with optional `:`-separated parameters. The
+/// parameters it leaves out come from the other sampler flags in `ScheduleSpec::cooling`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScheduleSpec {
+    Geometric { rate: Option<f64> },
+    Linear { step: Option<f64> },
+    Exponential { k: Option<f64> },
+    Logarithmic,
+    Adaptive { target: f64, band: f64 },
+    Reverse { low: f64 },
+}
+
+impl ScheduleSpec {
+    /// Each computed schedule with its default parameters, as `--compare-schedules` runs them.
+    pub const ALL: [ScheduleSpec; 6] = [
+        ScheduleSpec::Geometric { rate: None },
+        ScheduleSpec::Linear { step: None },
+        ScheduleSpec::Exponential { k: None },
+        ScheduleSpec::Logarithmic,
+        ScheduleSpec::Adaptive {
+            target: 0.3,
+            band: 0.05,
+        },
+        ScheduleSpec::Reverse { low: 0.25 },
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScheduleSpec::Geometric { .. } => "geometric",
+            ScheduleSpec::Linear { .. } => "linear",
+            ScheduleSpec::Exponential { .. } => "exponential",
+            ScheduleSpec::Logarithmic => "logarithmic",
+            ScheduleSpec::Adaptive { .. } => "adaptive",
+            ScheduleSpec::Reverse { .. } => "reverse",
+        }
+    }
+
+    /// The cooling for a run of `max_steps` from `start_temp`, filling in what the spec left
+    /// out: the rate of `geometric` and `adaptive` is `cooling_rate`, `exponential` decays
+    /// at `k = -ln(cooling_rate)`, and `linear` falls to zero over `max_steps`.
+    pub fn cooling(&self, start_temp: f64, cooling_rate: f64, max_steps: usize) -> Cooling {
+        match *self {
+            ScheduleSpec::Geometric { rate } => Cooling::Geometric {
+                rate: rate.unwrap_or(cooling_rate),
+            },
+            ScheduleSpec::Linear { step } => Cooling::Linear {
+                step: step.unwrap_or(start_temp / max_steps.max(1) as f64),
+            },
+            ScheduleSpec::Exponential { k } => Cooling::Exponential {
+                k: k.unwrap_or(-cooling_rate.ln()),
+            },
+            ScheduleSpec::Logarithmic => Cooling::Logarithmic,
+            ScheduleSpec::Adaptive { target, band } => Cooling::Adaptive {
+                target,
+                band,
+                rate: cooling_rate,
+            },
+            ScheduleSpec::Reverse { low } => Cooling::Reverse {
+                low,
+                steps: max_steps,
+            },
+        }
+    }
+
+    /// `cooling` paired with `start_temp`: the whole curve.
+    pub fn schedule(
+        &self,
+        start_temp: f64,
+        cooling_rate: f64,
+        max_steps: usize,
+    ) -> CoolingSchedule {
+        CoolingSchedule {
+            start_temp,
+            cooling: self.cooling(start_temp, cooling_rate, max_steps),
+        }
+    }
+}
+
+impl Default for ScheduleSpec {
+    fn default() -> Self {
+        ScheduleSpec::Geometric { rate: None }
+    }
+}
+
+impl fmt::Display for ScheduleSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ScheduleSpec {
+    type Err = String;
+
+    /// A schedule name with optional parameters: `geometric:RATE`, `linear:STEP`,
+    /// `exponential:K`, `adaptive:TARGET:BAND`, or `reverse:LOW`. Explicit schedules come
+    /// from a file (see `parse_temperatures`).
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts = text.split(':');
+        let name = parts.next().unwrap_or_default();
+        let schedule = ScheduleSpec::ALL
+            .into_iter()
+            .find(|schedule| schedule.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = ScheduleSpec::ALL.iter().map(ScheduleSpec::name).collect();
+                format!("unknown schedule {name:?} ({})", names.join(", "))
+            })?;
+        let parameters = parts
+            .map(|part| part.parse::<f64>().map_err(|err| format!("{part:?}: {err}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        match (&schedule, parameters.as_slice()) {
+            (_, []) => Ok(schedule),
+            (ScheduleSpec::Geometric { .. }, &[rate]) => {
+                Ok(ScheduleSpec::Geometric { rate: Some(rate) })
+            }
+            (ScheduleSpec::Linear { .. }, &[step]) => Ok(ScheduleSpec::Linear { step: Some(step) }),
+            (ScheduleSpec::Exponential { .. }, &[k]) => {
+                Ok(ScheduleSpec::Exponential { k: Some(k) })
+            }
+            (ScheduleSpec::Adaptive { .. }, &[target, band]) => {
+                Ok(ScheduleSpec::Adaptive { target, band })
+            }
+            (ScheduleSpec::Reverse { .. }, &[low]) => Ok(ScheduleSpec::Reverse { low }),
+            _ => Err(format!("unexpected parameters in schedule {text:?}")),
+        }
+    }
+}
+
+/// Reads an explicit schedule: one temperature per step, separated by whitespace, commas, or
+/// newlines. Lines starting with `#` are comments.
+pub fn parse_temperatures(text: &str) -> Result<Cooling, String> {
+    let temperatures = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.parse::<f64>() {
+            Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+            Ok(value) => Err(format!("temperature {value} is not a finite non-negative number")),
+            Err(err) => Err(format!("{token:?}: {err}")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if temperatures.is_empty() {
+        return Err("the schedule file lists no temperatures".to_string());
+    }
+    Ok(Cooling::Explicit(temperatures))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Temperatures of `cooling` from 2.4 before the first step and after each of
+    /// `max_steps`, with the acceptance ratio held at `ratio`: `curve[k]` follows step `k`.
+    fn curve(cooling: Cooling, max_steps: usize, ratio: Option<f64>) -> Vec<f64> {
+        let schedule = CoolingSchedule {
+            start_temp: 2.4,
+            cooling,
+        };
+        let mut temperature = schedule.initial_temp();
+        let mut curve = vec![temperature];
+        for step in 1..=max_steps {
+            temperature = schedule.next_temp_steered(step, temperature, ratio);
+            curve.push(temperature);
+        }
+        curve
+    }
+
+    fn non_increasing(curve: &[f64]) -> bool {
+        curve.windows(2).all(|pair| pair[1] <= pair[0])
+    }
+
+    #[test]
+    fn cooling_schedules_never_raise_the_temperature() {
+        let cooling = [
+            Cooling::Geometric { rate: 0.999 },
+            Cooling::Linear { step: 2.4 / 5_000.0 },
+            Cooling::Exponential { k: 1e-3 },
+            Cooling::Logarithmic,
+            Cooling::Explicit(vec![3.0, 2.0, 2.0, 0.5]),
+        ];
+        for cooling in cooling {
+            let name = cooling.name();
+            let curve = curve(cooling, 5_000, None);
+            assert!(non_increasing(&curve), "{name}");
+            assert!(curve[5_000] < curve[0], "{name}");
+        }
+        // Adaptive cools while acceptance is in or above its band and reheats below it.
+        let adaptive = Cooling::Adaptive {
+            target: 0.3,
+            band: 0.05,
+            rate: 0.999,
+        };
+        for ratio in [None, Some(0.3), Some(0.9)] {
+            assert!(non_increasing(&curve(adaptive.clone(), 5_000, ratio)), "{ratio:?}");
+        }
+        assert!(curve(adaptive, 10, Some(0.1)).is_sorted());
+    }
+
+    #[test]
+    fn linear_and_exponential_follow_their_parameters() {
+        let linear = curve(Cooling::Linear { step: 0.5 }, 10, None);
+        for (step, expected) in [2.4, 1.9, 1.4, 0.9, 0.4, 0.0].into_iter().enumerate() {
+            assert!((linear[step] - expected).abs() < 1e-12, "{step}");
+        }
+        assert_eq!(linear[10], 0.0);
+        let exponential = curve(Cooling::Exponential { k: 0.5 }, 4, None);
+        for (step, temperature) in exponential.iter().enumerate() {
+            assert!((temperature - 2.4 * (-0.5 * step as f64).exp()).abs() < 1e-12);
+        }
+        // The default k matches geometric cooling at `--cooling-rate` step for step.
+        let k = ScheduleSpec::Exponential { k: None }.cooling(2.4, 0.99, 100);
+        let geometric = curve(Cooling::Geometric { rate: 0.99 }, 100, None);
+        let exponential = curve(k, 100, None);
+        for (geometric, exponential) in geometric.iter().zip(&exponential) {
+            assert!((geometric - exponential).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn reverse_schedule_heats_to_start_temp_then_cools_back() {
+        let reverse = Cooling::Reverse {
+            low: 0.25,
+            steps: 5_000,
+        };
+        let curve = curve(reverse, 5_000, None);
+        // Step 2500 is the midpoint.
+        assert!(curve[..=2_500].is_sorted());
+        assert!(non_increasing(&curve[2_500..]));
+        assert_eq!(curve[0], 0.25);
+        assert!((curve[2_500] - 2.4).abs() < 1e-9);
+        assert!((curve[5_000] - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reheated_linear_falls_to_zero_over_the_remaining_steps() {
+        let schedule = ScheduleSpec::Linear { step: None }.schedule(2.0, 0.99, 1_000);
+        assert_eq!(schedule.cooling, Cooling::Linear { step: 0.002 });
+        let reheated = schedule.reheated(0.5, 400);
+        assert_eq!(reheated.start_temp, 1.0);
+        assert_eq!(reheated.cooling, Cooling::Linear { step: 1.0 / 400.0 });
+        let explicit = CoolingSchedule {
+            start_temp: 2.0,
+            cooling: Cooling::Explicit(vec![4.0, 2.0]),
+        };
+        assert_eq!(explicit.reheated(0.5, 10).explicit_temp(5), Some(1.0));
+    }
+
+    #[test]
+    fn schedule_specs_parse_their_parameters() {
+        let parsed = |text: &str| text.parse::<ScheduleSpec>();
+        assert_eq!(parsed("geometric"), Ok(ScheduleSpec::Geometric { rate: None }));
+        assert_eq!(parsed("Geometric:0.95"), Ok(ScheduleSpec::Geometric { rate: Some(0.95) }));
+        assert_eq!(parsed("linear:0.01"), Ok(ScheduleSpec::Linear { step: Some(0.01) }));
+        assert_eq!(parsed("exponential:0.002"), Ok(ScheduleSpec::Exponential { k: Some(0.002) }));
+        let adaptive = ScheduleSpec::Adaptive {
+            target: 0.4,
+            band: 0.1,
+        };
+        assert_eq!(parsed("adaptive:0.4:0.1"), Ok(adaptive));
+        assert_eq!(parsed("reverse:0.5"), Ok(ScheduleSpec::Reverse { low: 0.5 }));
+        assert!(parsed("logarithmic:2").is_err());
+        assert!(parsed("adaptive:0.4").is_err());
+        assert!(parsed("cubic").is_err());
+    }
+}
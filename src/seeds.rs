@@ -0,0 +1,59 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Increment of the SplitMix64 sequence (2^64 divided by the golden ratio).
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Child seed `index` of `master`: the `index`-th output of a SplitMix64 generator seeded
+/// with `master`. Each value depends only on `master` and `index`, so parallel work can
+/// derive its own seed without coordinating with other threads.
+pub fn derive_seed(master: u64, index: u64) -> u64 {
+    let mut z = master.wrapping_add(index.wrapping_add(1).wrapping_mul(GOLDEN_GAMMA));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The first `count` child seeds of `master`, for every feature that fans work out over
+/// threads. The finalizer is a bijection, so the seeds are distinct, and a prefix does not
+/// depend on `count`: the results stay the same whatever the thread count.
+pub fn derive_seeds(master: u64, count: usize) -> Vec<u64> {
+    (0..count as u64).map(|index| derive_seed(master, index)).collect()
+}
+
+/// Nanoseconds since the Unix epoch, truncated to 64 bits: a fresh seed on every run that is
+/// still a plain number, so it can be logged and passed back as `--seed`.
+pub fn seed_from_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn derived_seeds_follow_splitmix64() {
+        // Reference outputs of SplitMix64 seeded with 0.
+        assert_eq!(derive_seeds(0, 2), [0xe220_a839_7b1d_cdaf, 0x6e78_9e6a_a1b9_65f4]);
+    }
+
+    #[test]
+    fn derived_seeds_are_deterministic_distinct_and_prefix_stable() {
+        let seeds = derive_seeds(42, 1000);
+        assert_eq!(derive_seeds(42, 1000), seeds);
+        assert_eq!(seeds.iter().collect::<HashSet<_>>().len(), 1000);
+        assert_eq!(derive_seeds(42, 10), seeds[..10]);
+        assert_ne!(derive_seeds(43, 10), seeds[..10]);
+        // Four threads deriving a quarter each agree with the sequential list.
+        let quarter = |part: u64| -> Vec<u64> {
+            (part * 250..(part + 1) * 250).map(|index| derive_seed(42, index)).collect()
+        };
+        let threaded: Vec<u64> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|part| scope.spawn(move || quarter(part))).collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+        assert_eq!(threaded, seeds);
+    }
+}
@@ -1,25 +1,61 @@
-use rand::{rngs::StdRng, seq::SliceRandom, Rng};
-use std::collections::HashMap;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    error::Error,
+    fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    acceptance::AcceptanceRule,
+    schedule::{ACCEPTANCE_WINDOW, CoolingSchedule},
+};
+
+/// Fewest givens any uniquely solvable Sudoku can have (McGuire, Tugemann & Civario, 2012).
+pub const MIN_UNIQUE_GIVENS: usize = 17;
+
+/// Fewest holes the CLI generates; smaller `--holes` values are raised to this.
+pub const MIN_GENERATED_HOLES: usize = 16;
+
+/// The clamp the CLI applies to `--holes` before generating: at least `MIN_GENERATED_HOLES`,
+/// and at most `81 - MIN_UNIQUE_GIVENS` unless non-unique puzzles are allowed.
+pub fn clamp_holes(holes: usize, allow_nonunique: bool) -> usize {
+    let max_holes = if allow_nonunique {
+        81
+    } else {
+        81 - MIN_UNIQUE_GIVENS
+    };
+    holes.clamp(MIN_GENERATED_HOLES, max_holes)
+}
+
+/// The clamp the CLI applies to `--cooling-rate` before building a schedule from it: rates
+/// below 0.8 freeze a run within a few dozen swaps, and 1.0 or above never cools.
+pub fn clamp_cooling_rate(rate: f64) -> f64 {
+    rate.clamp(0.8, 0.9999)
+}
 
 #[derive(Clone)]
 pub struct SudokuState {
     pub board: [[u8; 9]; 9],
 }
 
+#[derive(Clone)]
 pub struct SudokuPuzzle {
     pub givens: [[Option<u8>; 9]; 9],
 }
 
 impl SudokuPuzzle {
-    pub fn with_random_holes(holes: usize, rng: &mut StdRng) -> Self {
-        let solution = generate_full_solution(rng);
-        let mut givens = [[None; 9]; 9];
-        for row in 0..9 {
-            for col in 0..9 {
-                givens[row][col] = Some(solution[row][col]);
-            }
-        }
-
+    pub fn with_random_holes<R: Rng + ?Sized>(holes: usize, rng: &mut R) -> Self {
+        let mut givens = solved_givens(rng);
         let mut coords: Vec<(usize, usize)> = (0..9)
             .flat_map(|row| (0..9).map(move |col| (row, col)))
             .collect();
@@ -32,7 +68,122 @@ impl SudokuPuzzle {
         SudokuPuzzle { givens }
     }
 
-    pub fn random_initial_state(&self, rng: &mut StdRng) -> SudokuState {
+    /// The puzzle `sudoku --seed SEED --holes HOLES` generates: `StdRng::seed_from_u64(seed)`,
+    /// the CLI's hole clamp, and `with_random_holes` as the first draws from that generator.
+    /// Stable for as long as `rand` keeps `StdRng` and the generation order unchanged.
+    pub fn reproduce(seed: u64, holes: usize) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self::with_random_holes(clamp_holes(holes, false), &mut rng)
+    }
+
+    /// Removes exactly `holes` cells (capped at 81) in a pattern with the given symmetry.
+    /// Under rotational symmetry every cell is paired with its 180° image except the centre,
+    /// which is its own image: an odd count clears the centre plus whole pairs, an even count
+    /// clears whole pairs only, so every count from 0 to 81 is reachable.
+    pub fn with_symmetric_holes<R: Rng + ?Sized>(
+        holes: usize,
+        symmetry: Symmetry,
+        rng: &mut R,
+    ) -> Self {
+        if symmetry == Symmetry::None {
+            return Self::with_random_holes(holes, rng);
+        }
+        let mut givens = solved_givens(rng);
+        let holes = holes.min(81);
+        let mut pairs: Vec<(usize, usize)> = (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(|&(row, col)| (row, col) < (8 - row, 8 - col))
+            .collect();
+        pairs.shuffle(rng);
+        if holes % 2 == 1 {
+            givens[4][4] = None;
+        }
+        for &(row, col) in pairs.iter().take(holes / 2) {
+            givens[row][col] = None;
+            givens[8 - row][8 - col] = None;
+        }
+
+        SudokuPuzzle { givens }
+    }
+
+    /// Generates puzzles with `holes` random holes until `config` solves one, giving up after
+    /// `attempts` puzzles. Each attempt draws the puzzle and then its solve from `rng`.
+    pub fn with_random_holes_solvable<R: Rng + ?Sized>(
+        holes: usize,
+        config: &SamplerConfig,
+        attempts: usize,
+        rng: &mut R,
+    ) -> Option<SolvablePuzzle> {
+        (1..=attempts).find_map(|attempt| {
+            let puzzle = Self::with_random_holes(holes, rng);
+            let (solution, stats) = solve(&puzzle, config, rng);
+            (stats.best_energy == 0).then_some(SolvablePuzzle {
+                puzzle,
+                solution,
+                stats,
+                attempts: attempt,
+            })
+        })
+    }
+
+    /// Parses 81 cells in row-major order, either as a single line or in the grid layout
+    /// printed by the CLI. Digits `1`-`9` are givens; `0`, `.`, and `_` are holes; whitespace
+    /// and the `|`, `+`, `-` grid decorations are ignored.
+    pub fn parse(text: &str) -> Result<Self, PuzzleParseError> {
+        if text.trim().is_empty() {
+            return Err(PuzzleParseError::Empty);
+        }
+        let mut cells = Vec::with_capacity(81);
+        for ch in text.chars() {
+            match ch {
+                '1'..='9' => cells.push(Some(ch as u8 - b'0')),
+                '0' | '.' | '_' => cells.push(None),
+                '|' | '+' | '-' => {}
+                ch if ch.is_whitespace() => {}
+                ch => return Err(PuzzleParseError::InvalidCharacter(ch)),
+            }
+        }
+        if cells.len() != 81 {
+            return Err(PuzzleParseError::WrongCellCount(cells.len()));
+        }
+        let mut givens = [[None; 9]; 9];
+        for (index, value) in cells.into_iter().enumerate() {
+            givens[index / 9][index % 9] = value;
+        }
+        Ok(SudokuPuzzle { givens })
+    }
+
+    /// `board` after `swaps` random exchanges of two free cells in the same line (column
+    /// under `Orientation::Columns`). Lines stay permutations and givens stay put, so the
+    /// result is a valid start for `solve_from`; later swaps may undo earlier ones. The swaps
+    /// are `anneal_step_with` proposals that are all accepted.
+    pub fn perturbed<R: Rng + ?Sized>(
+        &self,
+        board: &[[u8; 9]; 9],
+        swaps: usize,
+        orientation: Orientation,
+        rng: &mut R,
+    ) -> SudokuState {
+        let free = self.oriented(orientation).row_free_positions();
+        let config = SamplerConfig::default();
+        let start = SudokuState {
+            board: orientation.orient(board),
+        };
+        let mut anneal = AnnealState::new(start, &config);
+        let mut accept_all = |_, _, _: &mut R| true;
+        if free.iter().any(|positions| positions.len() >= 2) {
+            let mut made = 0;
+            while made < swaps {
+                let outcome = anneal_step_with(&mut anneal, &free, &config, rng, &mut accept_all);
+                made += usize::from(outcome.swap.is_some());
+            }
+        }
+        SudokuState {
+            board: orientation.orient(&anneal.state.board),
+        }
+    }
+
+    pub fn random_initial_state<R: Rng + ?Sized>(&self, rng: &mut R) -> SudokuState {
         let mut board = [[0u8; 9]; 9];
         for row in 0..9 {
             let mut digits: Vec<u8> = (1..=9).collect();
@@ -55,7 +206,43 @@ impl SudokuPuzzle {
         SudokuState { board }
     }
 
-    fn row_free_positions(&self) -> Vec<Vec<usize>> {
+    /// A random start, redrawn up to `config.initial_resamples` times while its energy exceeds
+    /// `config.initial_energy_cap`. If no draw gets under the cap the lowest-energy one is
+    /// kept. Returns the start and the number of redraws; without a cap this is one draw.
+    /// Under `Orientation::Columns` the start has permutations in its columns instead.
+    pub fn capped_initial_state<R: Rng + ?Sized>(
+        &self,
+        config: &SamplerConfig,
+        rng: &mut R,
+    ) -> (SudokuState, usize) {
+        let oriented = self.oriented(config.orientation);
+        let mut best = oriented.random_initial_state(rng);
+        let mut best_energy = config_energy(&best, config);
+        let mut resamples = 0;
+        if let Some(cap) = config.initial_energy_cap {
+            while best_energy > cap && resamples < config.initial_resamples {
+                resamples += 1;
+                let candidate = oriented.random_initial_state(rng);
+                let energy = config_energy(&candidate, config);
+                if energy < best_energy {
+                    best = candidate;
+                    best_energy = energy;
+                }
+            }
+        }
+        let board = config.orientation.orient(&best.board);
+        (SudokuState { board }, resamples)
+    }
+
+    /// The puzzle as the row-oriented sampler sees it under `orientation`.
+    pub fn oriented(&self, orientation: Orientation) -> SudokuPuzzle {
+        SudokuPuzzle {
+            givens: orientation.orient(&self.givens),
+        }
+    }
+
+    /// Columns of each row that are not givens, i.e. the cells the sampler may swap.
+    pub fn row_free_positions(&self) -> Vec<Vec<usize>> {
         (0..9)
             .map(|row| {
                 self.givens[row]
@@ -66,204 +253,2789 @@ impl SudokuPuzzle {
             })
             .collect()
     }
-}
 
-pub struct SamplerConfig {
-    pub max_steps: usize,
-    pub start_temp: f64,
-    pub cooling_rate: f64,
+    /// Every `(row, col)` that is not a given, in row-major order.
+    pub fn free_positions(&self) -> Vec<(usize, usize)> {
+        (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.givens[row][col].is_none())
+            .collect()
+    }
+
+    /// Number of holes, i.e. `81 - count_givens(&self.givens)`.
+    pub fn free_count(&self) -> usize {
+        81 - count_givens(&self.givens)
+    }
 }
 
-pub struct SolveStats {
-    pub steps: usize,
-    pub best_energy: usize,
-    pub temperature: f64,
+/// Pattern constraint on which cells a generated puzzle leaves empty.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// A hole at `(r, c)` implies a hole at `(8 - r, 8 - c)`.
+    Rotational,
 }
 
-pub fn solve(
-    puzzle: &SudokuPuzzle,
-    config: &SamplerConfig,
-    rng: &mut StdRng,
-) -> (SudokuState, SolveStats) {
-    let mut state = puzzle.random_initial_state(rng);
-    let mut energy = state.energy();
-    let mut best_state = state.clone();
-    let mut best_energy = energy;
-    let mut temperature = config.start_temp;
-    let cooling = config.cooling_rate.clamp(0.8, 0.9999);
-    let row_free = puzzle.row_free_positions();
-    let mut steps = 0;
+impl FromStr for Symmetry {
+    type Err = String;
 
-    for _ in 0..config.max_steps {
-        if energy == 0 {
-            break;
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" => Ok(Symmetry::None),
+            "rotational" => Ok(Symmetry::Rotational),
+            _ => Err(format!("unknown symmetry {name:?} (none, rotational)")),
         }
-        steps += 1;
-        let row = rng.random_range(0..9);
-        if let Some(positions) = row_free.get(row) {
-            if positions.len() < 2 {
-                continue;
-            }
-            let idx_a = rng.random_range(0..positions.len());
-            let mut idx_b = rng.random_range(0..positions.len());
-            while idx_b == idx_a {
-                idx_b = rng.random_range(0..positions.len());
-            }
-            let col_a = positions[idx_a];
-            let col_b = positions[idx_b];
-            state.board[row].swap(col_a, col_b);
-            let new_energy = state.energy();
-            let delta = new_energy as i64 - energy as i64;
-            let accept = if delta <= 0 {
-                true
-            } else {
-                let probability = (-(delta as f64) / temperature).exp().min(1.0);
-                rng.random_bool(probability)
-            };
-            if accept {
-                energy = new_energy;
-                if energy < best_energy {
-                    best_energy = energy;
-                    best_state = state.clone();
-                }
-            } else {
-                state.board[row].swap(col_a, col_b);
-            }
-            temperature = (temperature * cooling).max(0.25);
+    }
+}
+
+/// How hard a puzzle is for a human, as graded by `estimate_difficulty`.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Naked singles alone fill the board.
+    Easy,
+    /// Needs hidden singles as well.
+    Medium,
+    /// Singles stall, and the exact solver then finishes within `EXPERT_SEARCH_PLACEMENTS`.
+    Hard,
+    /// Singles stall and the search needs more placements than that.
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
         }
     }
+}
 
-    (
-        best_state,
-        SolveStats {
-            steps,
-            best_energy,
-            temperature,
-        },
-    )
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
-impl SudokuState {
-    fn energy(&self) -> usize {
-        column_conflicts(&self.board) + box_conflicts(&self.board)
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("unknown difficulty {name:?} (easy, medium, hard, expert)"))
     }
 }
 
-pub fn conflict_mask(board: &[[u8; 9]; 9]) -> [[bool; 9]; 9] {
-    let mut mask = [[false; 9]; 9];
-    for col in 0..9 {
-        let mut seen: HashMap<u8, Vec<usize>> = HashMap::new();
-        for row in 0..9 {
-            seen.entry(board[row][col]).or_default().push(row);
+/// Which lines the sampler keeps as permutations of 1-9.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Orientation {
+    /// Rows are permutations, swaps stay within a row, and the energy counts column and box
+    /// conflicts.
+    #[default]
+    Rows,
+    /// The mirror image: columns are permutations, swaps stay within a column, and the
+    /// energy counts row and box conflicts. Solved as the `Rows` problem on the transposed
+    /// puzzle, so `include_row_conflicts` and `GroupWeights::columns` then apply to columns
+    /// and rows respectively.
+    Columns,
+}
+
+impl Orientation {
+    /// `grid` as the row-oriented sampler sees it. Its own inverse.
+    pub fn orient<T: Copy>(&self, grid: &[[T; 9]; 9]) -> [[T; 9]; 9] {
+        match self {
+            Orientation::Rows => *grid,
+            Orientation::Columns => transpose(grid),
         }
-        for rows in seen.values() {
-            if rows.len() > 1 {
-                for &row in rows {
-                    mask[row][col] = true;
-                }
-            }
+    }
+
+    /// `conflict_mask` over the lines this orientation scores: rows and boxes under `Columns`.
+    pub fn conflict_mask(&self, board: &[[u8; 9]; 9]) -> [[bool; 9]; 9] {
+        self.orient(&conflict_mask(&self.orient(board)))
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "row" | "rows" => Ok(Orientation::Rows),
+            "column" | "columns" => Ok(Orientation::Columns),
+            _ => Err(format!("unknown orientation {name:?} (rows, columns)")),
         }
     }
+}
 
-    for block_row in 0..3 {
-        for block_col in 0..3 {
-            let mut seen: HashMap<u8, Vec<(usize, usize)>> = HashMap::new();
-            for row in (block_row * 3)..(block_row * 3 + 3) {
-                for col in (block_col * 3)..(block_col * 3 + 3) {
-                    seen.entry(board[row][col])
-                        .or_default()
-                        .push((row, col));
-                }
-            }
-            for cells in seen.values() {
-                if cells.len() > 1 {
-                    for &(row, col) in cells {
-                        mask[row][col] = true;
-                    }
-                }
-            }
+pub fn transpose<T: Copy>(grid: &[[T; 9]; 9]) -> [[T; 9]; 9] {
+    let mut transposed = *grid;
+    for (row, values) in grid.iter().enumerate() {
+        for (col, &value) in values.iter().enumerate() {
+            transposed[col][row] = value;
         }
     }
-    mask
+    transposed
 }
 
-pub fn count_givens(givens: &[[Option<u8>; 9]; 9]) -> usize {
-    givens.iter().flatten().filter(|value| value.is_some()).count()
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PuzzleParseError {
+    Empty,
+    InvalidCharacter(char),
+    WrongCellCount(usize),
 }
 
-fn column_conflicts(board: &[[u8; 9]; 9]) -> usize {
-    let mut conflicts = 0;
-    for col in 0..9 {
-        let mut counts = [0u8; 10];
-        for row in 0..9 {
-            let value = board[row][col] as usize;
-            counts[value] += 1;
-        }
-        for &count in counts.iter().skip(1) {
-            if count > 1 {
-                conflicts += (count - 1) as usize;
+impl fmt::Display for PuzzleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleParseError::Empty => write!(f, "puzzle input is empty"),
+            PuzzleParseError::InvalidCharacter(ch) => {
+                write!(f, "unexpected character {ch:?} in puzzle")
+            }
+            PuzzleParseError::WrongCellCount(count) => {
+                write!(f, "expected 81 cells, found {count}")
             }
         }
     }
-    conflicts
 }
 
-fn box_conflicts(board: &[[u8; 9]; 9]) -> usize {
-    let mut conflicts = 0;
-    for block_row in 0..3 {
-        for block_col in 0..3 {
-            let mut counts = [0u8; 10];
-            for row in (block_row * 3)..(block_row * 3 + 3) {
-                for col in (block_col * 3)..(block_col * 3 + 3) {
-                    let value = board[row][col] as usize;
-                    counts[value] += 1;
-                }
-            }
-            for &count in counts.iter().skip(1) {
-                if count > 1 {
-                    conflicts += (count - 1) as usize;
-                }
-            }
+impl Error for PuzzleParseError {}
+
+/// Step budget of `SamplerConfig::default()` and the CLI's `--max-steps`.
+pub const DEFAULT_MAX_STEPS: usize = 250_000;
+pub const DEFAULT_START_TEMP: f64 = 2.4;
+pub const DEFAULT_COOLING_RATE: f64 = 0.9995;
+pub const DEFAULT_TEMPERATURE_FLOOR: f64 = 0.25;
+/// Redraws allowed per solve once `initial_energy_cap` is set (`--initial-resamples`).
+pub const DEFAULT_INITIAL_RESAMPLES: usize = 10;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SamplerConfig {
+    pub max_steps: usize,
+    pub snapshot_decades: bool,
+    /// Scales each cell's share of column/box conflicts; `None` counts every conflict once.
+    pub cell_weights: Option<[[f64; 9]; 9]>,
+    /// After this many accepted uphill swaps in a row, the next uphill swap is rejected.
+    pub max_consecutive_uphill: Option<usize>,
+    /// Record every Nth accepted swap in `SolveStats::trace`; `None` disables tracing.
+    pub trace_every: Option<usize>,
+    /// Record every proposed swap, accepted or not, in `SolveStats::moves`.
+    pub record_moves: bool,
+    /// Probability of accepting a swap that leaves the energy unchanged (1.0 always accepts).
+    pub lateral_accept_prob: f64,
+    /// Lowest probability with which Metropolis accepts an uphill swap, however large its
+    /// `delta / T`; 0.0 leaves the Boltzmann probability as it is.
+    pub min_accept_prob: f64,
+    /// Also count duplicates within rows, for starting boards whose rows are not permutations.
+    pub include_row_conflicts: bool,
+    /// Cooling never takes the temperature below this value.
+    pub temperature_floor: f64,
+    /// After this many swaps without a new best energy, restart the schedule from
+    /// `reheat_factor` times its `start_temp` and carry on from the current board; `None` never
+    /// reheats. See `AnnealState::reheat`.
+    pub stagnation_window: Option<usize>,
+    pub reheat_factor: f64,
+    pub schedule: CoolingSchedule,
+    /// Recent proposals the acceptance ratio covers, and so what `Cooling::Adaptive` steers
+    /// by; a window of 0 is taken as 1.
+    pub acceptance_window: usize,
+    pub acceptance: AcceptanceRule,
+    /// Scales column and box conflicts as whole groups; ignored when `cell_weights` is set.
+    pub group_weights: Option<GroupWeights>,
+    /// Extra random starts drawn while the start's energy exceeds `initial_energy_cap`.
+    pub initial_resamples: usize,
+    /// Energy above which a random start is redrawn; `None` takes the first draw.
+    pub initial_energy_cap: Option<usize>,
+    pub orientation: Orientation,
+}
+
+impl Default for SamplerConfig {
+    /// The configuration `sudoku` runs with when no sampler flags are given.
+    fn default() -> Self {
+        SamplerConfig {
+            max_steps: DEFAULT_MAX_STEPS,
+            snapshot_decades: false,
+            cell_weights: None,
+            max_consecutive_uphill: None,
+            trace_every: None,
+            record_moves: false,
+            lateral_accept_prob: 1.0,
+            min_accept_prob: 0.0,
+            include_row_conflicts: false,
+            temperature_floor: DEFAULT_TEMPERATURE_FLOOR,
+            stagnation_window: None,
+            reheat_factor: 1.0,
+            schedule: CoolingSchedule::geometric(DEFAULT_START_TEMP, DEFAULT_COOLING_RATE),
+            acceptance_window: ACCEPTANCE_WINDOW,
+            acceptance: AcceptanceRule::default(),
+            group_weights: None,
+            initial_resamples: DEFAULT_INITIAL_RESAMPLES,
+            initial_energy_cap: None,
+            orientation: Orientation::default(),
         }
     }
-    conflicts
 }
 
-fn generate_full_solution(rng: &mut StdRng) -> [[u8; 9]; 9] {
-    let mut row_bands: Vec<usize> = (0..3).collect();
-    row_bands.shuffle(rng);
-    let mut rows = Vec::with_capacity(9);
-    for &band in &row_bands {
-        let mut offsets = vec![0, 1, 2];
-        offsets.shuffle(rng);
-        for offset in offsets {
-            rows.push(band * 3 + offset);
+/// Relative weight of column versus box conflicts in the annealing energy.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GroupWeights {
+    pub columns: f64,
+    pub boxes: f64,
+}
+
+pub struct SolveStats {
+    pub steps: usize,
+    pub best_energy: usize,
+    pub temperature: f64,
+    /// Share of the last `SamplerConfig::acceptance_window` proposals that were accepted when
+    /// the run ended; `None` if it ended before that many. What `Cooling::Adaptive` steers by.
+    pub acceptance_ratio: Option<f64>,
+    pub snapshots: Vec<DecadeSnapshot>,
+    pub trace: Vec<TracePoint>,
+    /// Every proposed swap in order, when `record_moves` is set; see `replay_moves`.
+    pub moves: Vec<MoveRecord>,
+    /// Swap at which cooling first reached `temperature_floor`, if it ever did.
+    pub floor_reached_at: Option<usize>,
+    /// Random starts redrawn because of `initial_energy_cap`; zero for a given start.
+    pub initial_resamples: usize,
+    /// Times the temperature was raised again after `stagnation_window` swaps without
+    /// improvement.
+    pub reheats: usize,
+    /// Board the chain started from, before the first swap, and its energy.
+    pub initial_board: [[u8; 9]; 9],
+    pub initial_energy: usize,
+}
+
+/// Outcome of `solve_escalating`: the last try and how it was reached.
+pub struct Escalation {
+    pub solution: SudokuState,
+    pub stats: SolveStats,
+    /// Step budget of the last try, the one that solved the puzzle if any did.
+    pub budget: usize,
+    pub tries: usize,
+    /// Swaps summed over every try.
+    pub total_steps: usize,
+}
+
+/// A generated puzzle together with the annealer run that solved it.
+pub struct SolvablePuzzle {
+    pub puzzle: SudokuPuzzle,
+    pub solution: SudokuState,
+    pub stats: SolveStats,
+    /// Puzzles generated before one was solved, this one included.
+    pub attempts: usize,
+}
+
+/// Sampler state right after an accepted swap; the last point is always the final state.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TracePoint {
+    pub step: usize,
+    pub energy: usize,
+    pub best_energy: usize,
+    pub temperature: f64,
+}
+
+/// One proposed swap of a solve. Coordinates are in the sampler's frame: under
+/// `Orientation::Columns`, `row` is a column of the board and `col_a`/`col_b` are rows.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    pub row: usize,
+    pub col_a: usize,
+    pub col_b: usize,
+    pub accepted: bool,
+}
+
+/// Best board seen when the temperature first dropped below a power of ten. A reheat starts
+/// the count again, so each cooling pass after it gets its own snapshots.
+pub struct DecadeSnapshot {
+    pub decade: f64,
+    pub step: usize,
+    pub temperature: f64,
+    pub best_energy: usize,
+    pub board: [[u8; 9]; 9],
+}
+
+/// Sampler state threaded through `anneal_step`, so a front-end can drive the chain one
+/// proposal at a time.
+#[derive(Clone)]
+pub struct AnnealState {
+    pub state: SudokuState,
+    /// Conflict count of `state` (including rows when `include_row_conflicts` is set).
+    pub energy: usize,
+    /// Energy as the acceptance test sees it; differs from `energy` only under weights.
+    pub score: f64,
+    pub temperature: f64,
+    /// Threshold or water level of a non-Metropolis `AcceptanceRule`; unused otherwise.
+    pub level: f64,
+    /// Proposals made so far, counting rows that had nothing to swap.
+    pub steps: usize,
+    uphill_run: usize,
+    /// Step at which the schedule's current curve began; moved forward by each reheat.
+    origin: usize,
+    /// The schedule as the last reheat left it; `SamplerConfig::schedule` until then.
+    curve: CoolingSchedule,
+    /// Proposals `recent` holds once full.
+    window: usize,
+    /// Accept/reject outcomes of the last `window` proposals.
+    recent: VecDeque<bool>,
+    recent_accepted: usize,
+}
+
+impl AnnealState {
+    pub fn new(state: SudokuState, config: &SamplerConfig) -> Self {
+        let energy = config_energy(&state, config);
+        let score = config_score(&state, energy, config);
+        AnnealState {
+            state,
+            energy,
+            score,
+            temperature: config.schedule.initial_temp().max(config.temperature_floor),
+            level: config.acceptance.initial_level().unwrap_or(0.0),
+            steps: 0,
+            uphill_run: 0,
+            origin: 0,
+            curve: config.schedule.clone(),
+            window: config.acceptance_window.max(1),
+            recent: VecDeque::with_capacity(config.acceptance_window.max(1) + 1),
+            recent_accepted: 0,
         }
     }
 
-    let mut col_bands: Vec<usize> = (0..3).collect();
-    col_bands.shuffle(rng);
-    let mut cols = Vec::with_capacity(9);
-    for &band in &col_bands {
-        let mut offsets = vec![0, 1, 2];
-        offsets.shuffle(rng);
-        for offset in offsets {
-            cols.push(band * 3 + offset);
+    /// Share of the last `acceptance_window` proposals that were accepted; `None` until that
+    /// many swaps have been proposed.
+    pub fn acceptance_ratio(&self) -> Option<f64> {
+        (self.recent.len() == self.window)
+            .then(|| self.recent_accepted as f64 / self.window as f64)
+    }
+
+    /// Restarts the cooling schedule at the current step, as if the run began here with
+    /// `start_temp` scaled by `factor` and the remaining steps as its budget. The board is
+    /// left as it is.
+    pub fn reheat(&mut self, config: &SamplerConfig, factor: f64) {
+        self.origin = self.steps;
+        let steps_left = config.max_steps.saturating_sub(self.origin);
+        self.curve = config.schedule.reheated(factor, steps_left);
+        self.temperature = self.curve.initial_temp().max(config.temperature_floor);
+    }
+
+    /// Temperature the schedule gives after the current step, counted from the curve's origin.
+    fn scheduled_temperature(&self, config: &SamplerConfig, current: f64) -> f64 {
+        let step = self.steps - self.origin;
+        self.curve
+            .next_temp_steered(step, current, self.acceptance_ratio())
+            .max(config.temperature_floor)
+    }
+
+    fn record_outcome(&mut self, accepted: bool) {
+        self.recent.push_back(accepted);
+        self.recent_accepted += usize::from(accepted);
+        if self.recent.len() > self.window && self.recent.pop_front() == Some(true) {
+            self.recent_accepted -= 1;
         }
     }
+}
 
-    let mut nums: Vec<u8> = (1..=9).collect();
-    nums.shuffle(rng);
+/// What a single `anneal_step` did.
+#[derive(Clone, Copy, Debug)]
+pub struct StepOutcome {
+    /// `(row, col_a, col_b)` of the proposed swap; `None` when the drawn row had fewer than
+    /// two free cells, in which case nothing else happened (not even cooling).
+    pub swap: Option<(usize, usize, usize)>,
+    pub delta: f64,
+    pub accepted: bool,
+    /// Temperature the acceptance test used, before this step's cooling.
+    pub temperature: f64,
+}
 
-    let mut board = [[0u8; 9]; 9];
-    for (i, &row) in rows.iter().enumerate() {
-        for (j, &col) in cols.iter().enumerate() {
-            board[i][j] = nums[pattern(row, col)];
+/// One proposal, accept/reject decision, and cooling step. `free` lists the non-given
+/// columns of each row, as returned by `SudokuPuzzle::row_free_positions`.
+pub fn anneal_step<R: Rng + ?Sized>(
+    anneal: &mut AnnealState,
+    free: &[Vec<usize>],
+    config: &SamplerConfig,
+    rng: &mut R,
+) -> StepOutcome {
+    let floor = config.min_accept_prob;
+    anneal_step_with(anneal, free, config, rng, &mut |delta, temperature, rng: &mut R| {
+        metropolis_floored(delta, temperature, floor, rng)
+    })
+}
+
+/// The Boltzmann-Metropolis rule for an uphill move: accept with probability
+/// `exp(-delta / temperature)`. The default acceptance function.
+pub fn metropolis<R: Rng + ?Sized>(delta: f64, temperature: f64, rng: &mut R) -> bool {
+    metropolis_floored(delta, temperature, 0.0, rng)
+}
+
+/// `metropolis` with the probability raised to at least `floor`, so that no uphill move
+/// becomes impossible once `exp(-delta / temperature)` underflows.
+pub fn metropolis_floored<R: Rng + ?Sized>(
+    delta: f64,
+    temperature: f64,
+    floor: f64,
+    rng: &mut R,
+) -> bool {
+    let probability = (-delta / temperature).exp().max(floor).min(1.0);
+    rng.random_bool(probability)
+}
+
+/// `anneal_step` with `accept(delta, temperature, rng)` deciding uphill moves in place of
+/// `metropolis`, e.g. threshold accepting or record-to-record travel. Downhill moves are
+/// always taken, plateau moves follow `lateral_accept_prob`, and `max_consecutive_uphill`
+/// still rejects before `accept` is asked.
+pub fn anneal_step_with<R, F>(
+    anneal: &mut AnnealState,
+    free: &[Vec<usize>],
+    config: &SamplerConfig,
+    rng: &mut R,
+    accept: &mut F,
+) -> StepOutcome
+where
+    R: Rng + ?Sized,
+    F: FnMut(f64, f64, &mut R) -> bool,
+{
+    anneal.steps += 1;
+    let temperature = anneal.temperature;
+    let row = rng.random_range(0..9);
+    let positions = &free[row];
+    if positions.len() < 2 {
+        // Computed schedules only cool after a proposal, but an explicit one stays on step.
+        if config.schedule.explicit_temp(0).is_some() {
+            anneal.temperature = anneal.scheduled_temperature(config, temperature);
         }
+        return StepOutcome {
+            swap: None,
+            delta: 0.0,
+            accepted: false,
+            temperature,
+        };
+    }
+    let idx_a = rng.random_range(0..positions.len());
+    let mut idx_b = rng.random_range(0..positions.len());
+    while idx_b == idx_a {
+        idx_b = rng.random_range(0..positions.len());
+    }
+    let col_a = positions[idx_a];
+    let col_b = positions[idx_b];
+    let state = &mut anneal.state;
+    state.board[row].swap(col_a, col_b);
+    let new_energy = config_energy(state, config);
+    let new_score = config_score(state, new_energy, config);
+    let delta = new_score - anneal.score;
+    let accepted = if delta < 0.0 {
+        true
+    } else if delta == 0.0 {
+        accept_lateral(config.lateral_accept_prob, rng)
+    } else if config
+        .max_consecutive_uphill
+        .is_some_and(|cap| anneal.uphill_run >= cap)
+    {
+        anneal.uphill_run = 0;
+        false
+    } else {
+        config
+            .acceptance
+            .accepts(anneal.score, delta, anneal.level)
+            .unwrap_or_else(|| accept(delta, temperature, rng))
+    };
+    if accepted {
+        anneal.uphill_run = if delta > 0.0 { anneal.uphill_run + 1 } else { 0 };
+        anneal.energy = new_energy;
+        anneal.score = new_score;
+    } else {
+        state.board[row].swap(col_a, col_b);
+    }
+    anneal.record_outcome(accepted);
+    anneal.temperature = anneal.scheduled_temperature(config, temperature);
+    anneal.level = config.acceptance.next_level(anneal.level);
+    StepOutcome {
+        swap: Some((row, col_a, col_b)),
+        delta,
+        accepted,
+        temperature,
     }
-    board
 }
 
-fn pattern(row: usize, col: usize) -> usize {
-    (3 * (row % 3) + row / 3 + col) % 9
+pub fn solve<R: Rng + ?Sized>(
+    puzzle: &SudokuPuzzle,
+    config: &SamplerConfig,
+    rng: &mut R,
+) -> (SudokuState, SolveStats) {
+    let floor = config.min_accept_prob;
+    solve_with_acceptance(puzzle, config, rng, |delta, temperature, rng: &mut R| {
+        metropolis_floored(delta, temperature, floor, rng)
+    })
+}
+
+/// `solve` with a custom rule for uphill moves; see `anneal_step_with`.
+pub fn solve_with_acceptance<R, F>(
+    puzzle: &SudokuPuzzle,
+    config: &SamplerConfig,
+    rng: &mut R,
+    accept: F,
+) -> (SudokuState, SolveStats)
+where
+    R: Rng + ?Sized,
+    F: FnMut(f64, f64, &mut R) -> bool,
+{
+    let (start, resamples) = puzzle.capped_initial_state(config, rng);
+    let (solution, mut stats) = anneal_from(puzzle, start, config, rng, accept);
+    stats.initial_resamples = resamples;
+    (solution, stats)
+}
+
+/// Iterative deepening of the step budget: solves with `initial_budget` swaps, and while the
+/// puzzle stays unsolved retries from a fresh random start with twice the budget, up to
+/// `config.max_steps`. Nothing carries over between tries except the RNG.
+pub fn solve_escalating<R: Rng + ?Sized>(
+    puzzle: &SudokuPuzzle,
+    config: &SamplerConfig,
+    initial_budget: usize,
+    rng: &mut R,
+) -> Escalation {
+    let mut budget = initial_budget.clamp(1, config.max_steps.max(1));
+    let mut tries = 0;
+    let mut total_steps = 0;
+    loop {
+        let try_config = SamplerConfig {
+            max_steps: budget,
+            ..config.clone()
+        };
+        let (solution, stats) = solve(puzzle, &try_config, rng);
+        tries += 1;
+        total_steps += stats.steps;
+        if stats.best_energy == 0 || budget >= config.max_steps {
+            return Escalation {
+                solution,
+                stats,
+                budget,
+                tries,
+                total_steps,
+            };
+        }
+        budget = budget.saturating_mul(2).min(config.max_steps);
+    }
+}
+
+/// `solve` from a given board instead of a random one, e.g. a near-solution to refine. The
+/// board must hold the givens and keep every row a permutation of 1-9, because swaps never
+/// change which digits a row contains.
+pub fn solve_from<R: Rng + ?Sized>(
+    puzzle: &SudokuPuzzle,
+    start: SudokuState,
+    config: &SamplerConfig,
+    rng: &mut R,
+) -> (SudokuState, SolveStats) {
+    let floor = config.min_accept_prob;
+    anneal_from(puzzle, start, config, rng, |delta, temperature, rng: &mut R| {
+        metropolis_floored(delta, temperature, floor, rng)
+    })
+}
+
+/// Solves `puzzles[i]` from `StdRng::seed_from_u64(seeds[i])` on up to `jobs` threads and
+/// returns the results in input order, each with its wall-clock time. Every solve depends
+/// only on its puzzle and seed, so the results match a one-thread run whatever `jobs` is.
+pub fn solve_batch(
+    puzzles: &[SudokuPuzzle],
+    seeds: &[u64],
+    config: &SamplerConfig,
+    jobs: usize,
+) -> Vec<(SudokuState, SolveStats, Duration)> {
+    let Ok(results) =
+        solve_batch_with(puzzles, seeds, config, jobs, |_, _, _, _| Ok::<_, Infallible>(()));
+    results
+}
+
+/// `solve_batch` that also hands each result to `on_done` on the calling thread as soon as
+/// it finishes, with its input index: in completion order when `jobs > 1`. An error from
+/// `on_done` stops the batch once the solves in flight have finished.
+pub fn solve_batch_with<E, F>(
+    puzzles: &[SudokuPuzzle],
+    seeds: &[u64],
+    config: &SamplerConfig,
+    jobs: usize,
+    mut on_done: F,
+) -> Result<Vec<(SudokuState, SolveStats, Duration)>, E>
+where
+    F: FnMut(usize, &SudokuState, &SolveStats, Duration) -> Result<(), E>,
+{
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..jobs.clamp(1, puzzles.len().max(1)) {
+            let (sender, next) = (sender.clone(), &next);
+            scope.spawn(move || {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let (Some(puzzle), Some(&seed)) = (puzzles.get(index), seeds.get(index))
+                    else {
+                        break;
+                    };
+                    let started = Instant::now();
+                    let (solution, stats) =
+                        solve(puzzle, config, &mut StdRng::seed_from_u64(seed));
+                    if sender.send((index, (solution, stats, started.elapsed()))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        let mut results = Vec::with_capacity(puzzles.len());
+        for (index, (solution, stats, elapsed)) in receiver {
+            on_done(index, &solution, &stats, elapsed)?;
+            results.push((index, (solution, stats, elapsed)));
+        }
+        Ok(results)
+    })?;
+    results.sort_by_key(|&(index, _)| index);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+fn anneal_from<R, F>(
+    puzzle: &SudokuPuzzle,
+    start: SudokuState,
+    config: &SamplerConfig,
+    rng: &mut R,
+    mut accept: F,
+) -> (SudokuState, SolveStats)
+where
+    R: Rng + ?Sized,
+    F: FnMut(f64, f64, &mut R) -> bool,
+{
+    // Solve in the frame where rows are the permutations; cell weights follow their cells.
+    let orientation = config.orientation;
+    let puzzle = &puzzle.oriented(orientation);
+    let start = SudokuState {
+        board: orientation.orient(&start.board),
+    };
+    let config = &SamplerConfig {
+        cell_weights: config.cell_weights.map(|weights| orientation.orient(&weights)),
+        ..config.clone()
+    };
+    let mut anneal = AnnealState::new(start, config);
+    let (initial_board, initial_energy) = (orientation.orient(&anneal.state.board), anneal.energy);
+    let mut best_state = anneal.state.clone();
+    let mut best_energy = anneal.energy;
+    let mut best_score = anneal.score;
+    let row_free = puzzle.row_free_positions();
+    let mut snapshots = Vec::new();
+    let mut decade = anneal.temperature.log10().floor() as i32;
+    let mut trace = Vec::new();
+    let mut moves = Vec::new();
+    let mut accepted = 0;
+    let mut floor_reached_at = None;
+    let (mut last_improvement, mut reheats) = (0, 0);
+
+    for _ in 0..config.max_steps {
+        if anneal.energy == 0 {
+            break;
+        }
+        let outcome = anneal_step_with(&mut anneal, &row_free, config, rng, &mut accept);
+        let Some((row, col_a, col_b)) = outcome.swap else {
+            continue;
+        };
+        if config.record_moves {
+            moves.push(MoveRecord {
+                row,
+                col_a,
+                col_b,
+                accepted: outcome.accepted,
+            });
+        }
+        if outcome.accepted {
+            if improves_best(anneal.score, anneal.energy, best_score, best_energy) {
+                best_score = anneal.score;
+                best_energy = anneal.energy;
+                best_state = anneal.state.clone();
+                last_improvement = anneal.steps;
+            }
+            accepted += 1;
+            if config.trace_every.is_some_and(|every| accepted % every.max(1) == 0) {
+                trace.push(TracePoint {
+                    step: anneal.steps,
+                    energy: anneal.energy,
+                    best_energy,
+                    temperature: outcome.temperature,
+                });
+            }
+        }
+        if floor_reached_at.is_none() && anneal.temperature <= config.temperature_floor {
+            floor_reached_at = Some(anneal.steps);
+        }
+        if config
+            .stagnation_window
+            .is_some_and(|window| anneal.steps - last_improvement >= window.max(1))
+        {
+            anneal.reheat(config, config.reheat_factor);
+            decade = anneal.temperature.log10().floor() as i32;
+            last_improvement = anneal.steps;
+            reheats += 1;
+        }
+        if config.snapshot_decades {
+            while anneal.temperature < 10f64.powi(decade) {
+                snapshots.push(DecadeSnapshot {
+                    decade: 10f64.powi(decade),
+                    step: anneal.steps,
+                    temperature: anneal.temperature,
+                    best_energy,
+                    board: best_state.board,
+                });
+                decade -= 1;
+            }
+        }
+    }
+
+    debug_assert!(
+        best_energy != 0 || is_valid_solution(&best_state.board),
+        "energy tracking reported a solution for an invalid board"
+    );
+    let steps = anneal.steps;
+    if config.trace_every.is_some() && trace.last().is_none_or(|point| point.step != steps) {
+        trace.push(TracePoint {
+            step: steps,
+            energy: anneal.energy,
+            best_energy,
+            temperature: anneal.temperature,
+        });
+    }
+
+    for snapshot in &mut snapshots {
+        snapshot.board = orientation.orient(&snapshot.board);
+    }
+    (
+        SudokuState {
+            board: orientation.orient(&best_state.board),
+        },
+        SolveStats {
+            steps,
+            best_energy,
+            temperature: anneal.temperature,
+            acceptance_ratio: anneal.acceptance_ratio(),
+            snapshots,
+            trace,
+            moves,
+            floor_reached_at,
+            initial_resamples: 0,
+            reheats,
+            initial_board,
+            initial_energy,
+        },
+    )
+}
+
+/// Replays recorded `moves` from `start` without an RNG, returning the best board and its
+/// energy exactly as the recording solve reported them. `config` must be the one the moves
+/// were recorded under, since its orientation and weights decide which board counts as best.
+pub fn replay_moves(
+    start: &[[u8; 9]; 9],
+    moves: &[MoveRecord],
+    config: &SamplerConfig,
+) -> (SudokuState, usize) {
+    let orientation = config.orientation;
+    let config = &SamplerConfig {
+        cell_weights: config.cell_weights.map(|weights| orientation.orient(&weights)),
+        ..config.clone()
+    };
+    let mut state = SudokuState {
+        board: orientation.orient(start),
+    };
+    let mut best_state = state.clone();
+    let mut best_energy = config_energy(&state, config);
+    let mut best_score = config_score(&state, best_energy, config);
+    for record in moves.iter().filter(|record| record.accepted) {
+        state.board[record.row].swap(record.col_a, record.col_b);
+        let energy = config_energy(&state, config);
+        let score = config_score(&state, energy, config);
+        if improves_best(score, energy, best_score, best_energy) {
+            best_score = score;
+            best_energy = energy;
+            best_state = state.clone();
+        }
+    }
+    let board = orientation.orient(&best_state.board);
+    (SudokuState { board }, best_energy)
+}
+
+/// Conflicts the sampler minimises: columns and boxes, plus rows when configured.
+fn config_energy(state: &SudokuState, config: &SamplerConfig) -> usize {
+    let rows = if config.include_row_conflicts {
+        row_conflicts(&state.board)
+    } else {
+        0
+    };
+    state.energy() + rows
+}
+
+/// Whether a state with `score` and `energy` replaces the best one so far. A solution is the
+/// best state even if zero weights scored another board lower.
+fn improves_best(score: f64, energy: usize, best_score: f64, best_energy: usize) -> bool {
+    score < best_score || (energy == 0 && best_energy != 0)
+}
+
+/// `energy` reshaped by the configured cell or group weights, if any.
+fn config_score(state: &SudokuState, energy: usize, config: &SamplerConfig) -> f64 {
+    let board = &state.board;
+    let rows = || {
+        if config.include_row_conflicts {
+            row_conflicts(board) as f64
+        } else {
+            0.0
+        }
+    };
+    match (&config.cell_weights, config.group_weights) {
+        (Some(weights), _) => weighted_energy(board, weights) + rows(),
+        (None, Some(groups)) => {
+            groups.columns * column_conflicts(board) as f64
+                + groups.boxes * box_conflicts(board) as f64
+                + rows()
+        }
+        (None, None) => energy as f64,
+    }
+}
+
+/// Plateau moves are always taken at probability 1.0 without drawing from the RNG, so the
+/// default leaves seeded runs unchanged.
+fn accept_lateral<R: Rng + ?Sized>(probability: f64, rng: &mut R) -> bool {
+    probability >= 1.0 || (probability > 0.0 && rng.random_bool(probability))
+}
+
+/// Independent `sample_fixed_temperature` chains of `puzzle`, chain `i` drawing from
+/// `StdRng::seed_from_u64(seeds[i])`, on up to `jobs` threads. Chains come back in seed
+/// order, so the ensemble is the same whatever `jobs` is.
+pub fn sample_ensemble(
+    puzzle: &SudokuPuzzle,
+    temperature: f64,
+    steps: usize,
+    seeds: &[u64],
+    jobs: usize,
+) -> Vec<Vec<usize>> {
+    let next = AtomicUsize::new(0);
+    let mut chains = thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..jobs.clamp(1, seeds.len().max(1)) {
+            let (sender, next) = (sender.clone(), &next);
+            scope.spawn(move || {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&seed) = seeds.get(index) else {
+                        break;
+                    };
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let samples = sample_fixed_temperature(puzzle, temperature, steps, &mut rng);
+                    if sender.send((index, samples)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        receiver.into_iter().collect::<Vec<_>>()
+    });
+    chains.sort_by_key(|&(index, _)| index);
+    chains.into_iter().map(|(_, samples)| samples).collect()
+}
+
+/// Metropolis chain held at a constant temperature: `anneal_step` on a schedule that never
+/// cools. Returns the energy after each proposal.
+pub fn sample_fixed_temperature<R: Rng + ?Sized>(
+    puzzle: &SudokuPuzzle,
+    temperature: f64,
+    steps: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    let config = SamplerConfig {
+        max_steps: steps,
+        temperature_floor: 0.0,
+        schedule: CoolingSchedule::geometric(temperature, 1.0),
+        ..SamplerConfig::default()
+    };
+    let free = puzzle.row_free_positions();
+    let mut anneal = AnnealState::new(puzzle.random_initial_state(rng), &config);
+    (0..steps)
+        .map(|_| {
+            anneal_step(&mut anneal, &free, &config, rng);
+            anneal.energy
+        })
+        .collect()
+}
+
+impl SudokuState {
+    fn energy(&self) -> usize {
+        board_energy(&self.board)
+    }
+}
+
+/// Hamming distance between two boards: the number of cells holding different digits.
+pub fn board_distance(a: &[[u8; 9]; 9], b: &[[u8; 9]; 9]) -> usize {
+    a.iter()
+        .flatten()
+        .zip(b.iter().flatten())
+        .filter(|(x, y)| x != y)
+        .count()
+}
+
+/// Column plus box conflicts of a raw board, the energy the sampler minimises (rows are
+/// assumed to be permutations, as the sampler keeps them).
+pub fn board_energy(board: &[[u8; 9]; 9]) -> usize {
+    column_conflicts(board) + box_conflicts(board)
+}
+
+/// `board_energy` of each board, in input order.
+pub fn score_all<'a>(boards: impl IntoIterator<Item = &'a [[u8; 9]; 9]>) -> Vec<usize> {
+    boards.into_iter().map(board_energy).collect()
+}
+
+/// Completions `suggest_fix` compares against the board before settling for the nearest.
+pub const FIX_COMPLETIONS: usize = 1000;
+
+/// A cell whose digit differs from the nearest valid completion.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CellFix {
+    pub row: usize,
+    pub col: usize,
+    pub from: u8,
+    pub to: u8,
+}
+
+/// Second pass for a best-effort board: the fewest cell changes that turn `board` into a
+/// valid completion of `puzzle`, taking the nearest of the first `FIX_COMPLETIONS`
+/// completions the exact solver finds. Empty for a solved board; `None` when the givens
+/// have no completion at all.
+pub fn suggest_fix(puzzle: &SudokuPuzzle, board: &[[u8; 9]; 9]) -> Option<Vec<CellFix>> {
+    let fixes = |completion: &SudokuState| {
+        (0..81)
+            .map(|cell| (cell / 9, cell % 9))
+            .filter(|&(row, col)| completion.board[row][col] != board[row][col])
+            .map(|(row, col)| CellFix {
+                row,
+                col,
+                from: board[row][col],
+                to: completion.board[row][col],
+            })
+            .collect::<Vec<_>>()
+    };
+    completions_iter(puzzle)
+        .take(FIX_COMPLETIONS)
+        .map(|completion| fixes(&completion))
+        .min_by_key(Vec::len)
+}
+
+/// `fixes` as `(row, col_a, col_b)` swaps, when every change exchanges two digits within a
+/// row; `None` if any change is not part of such a swap.
+pub fn fixes_as_swaps(fixes: &[CellFix]) -> Option<Vec<(usize, usize, usize)>> {
+    let mut swaps = Vec::new();
+    let mut remaining = fixes.to_vec();
+    while let Some(fix) = remaining.pop() {
+        let partner = remaining.iter().position(|other| {
+            other.row == fix.row && other.from == fix.to && other.to == fix.from
+        })?;
+        let other = remaining.swap_remove(partner);
+        swaps.push((fix.row, other.col.min(fix.col), other.col.max(fix.col)));
+    }
+    swaps.sort_unstable();
+    Some(swaps)
+}
+
+/// Greedy hint: the `(row, col_a, col_b)` swap of two free cells that lowers the energy
+/// the most, or `None` when no single swap improves the board.
+pub fn best_single_move(
+    state: &SudokuState,
+    givens: &[[Option<u8>; 9]; 9],
+) -> Option<(usize, usize, usize)> {
+    let mut board = state.board;
+    let mut best_energy = state.energy();
+    let mut best_move = None;
+    for row in 0..9 {
+        for col_a in 0..9 {
+            if givens[row][col_a].is_some() {
+                continue;
+            }
+            for col_b in (col_a + 1)..9 {
+                if givens[row][col_b].is_some() {
+                    continue;
+                }
+                board[row].swap(col_a, col_b);
+                let energy = board_energy(&board);
+                board[row].swap(col_a, col_b);
+                if energy < best_energy {
+                    best_energy = energy;
+                    best_move = Some((row, col_a, col_b));
+                }
+            }
+        }
+    }
+    best_move
+}
+
+pub fn conflict_mask(board: &[[u8; 9]; 9]) -> [[bool; 9]; 9] {
+    let mut mask = [[false; 9]; 9];
+    for col in 0..9 {
+        let mut seen: HashMap<u8, Vec<usize>> = HashMap::new();
+        for row in 0..9 {
+            seen.entry(board[row][col]).or_default().push(row);
+        }
+        for rows in seen.values() {
+            if rows.len() > 1 {
+                for &row in rows {
+                    mask[row][col] = true;
+                }
+            }
+        }
+    }
+
+    for block_row in 0..3 {
+        for block_col in 0..3 {
+            let mut seen: HashMap<u8, Vec<(usize, usize)>> = HashMap::new();
+            for row in (block_row * 3)..(block_row * 3 + 3) {
+                for col in (block_col * 3)..(block_col * 3 + 3) {
+                    seen.entry(board[row][col])
+                        .or_default()
+                        .push((row, col));
+                }
+            }
+            for cells in seen.values() {
+                if cells.len() > 1 {
+                    for &(row, col) in cells {
+                        mask[row][col] = true;
+                    }
+                }
+            }
+        }
+    }
+    mask
+}
+
+/// For every cell, how many other cells in its column or box hold the same digit; a peer in
+/// both is counted once. Non-zero exactly where `conflict_mask` is set.
+pub fn conflict_counts(board: &[[u8; 9]; 9]) -> [[usize; 9]; 9] {
+    let mut counts = [[0; 9]; 9];
+    for row in 0..9 {
+        for col in 0..9 {
+            let block = box_index(row, col);
+            counts[row][col] = (0..81)
+                .map(|index| (index / 9, index % 9))
+                .filter(|&(peer_row, peer_col)| {
+                    (peer_row, peer_col) != (row, col)
+                        && (peer_col == col || box_index(peer_row, peer_col) == block)
+                        && board[peer_row][peer_col] == board[row][col]
+                })
+                .count();
+        }
+    }
+    counts
+}
+
+/// Conflicting cells with their `conflict_counts`, worst first; equal counts keep row-major
+/// order, so the ranking is deterministic.
+pub fn conflicts_ranked(board: &[[u8; 9]; 9]) -> Vec<((usize, usize), usize)> {
+    let counts = conflict_counts(board);
+    let mut ranked: Vec<((usize, usize), usize)> = (0..81)
+        .map(|index| ((index / 9, index % 9), counts[index / 9][index % 9]))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    ranked.sort_by_key(|&(_, count)| Reverse(count));
+    ranked
+}
+
+/// Per-column and per-box digit counts for a board under row swaps, keeping the conflict
+/// mask current by re-deriving only the cells a swap can affect instead of rebuilding it.
+#[derive(Clone)]
+pub struct ConflictTracker {
+    board: [[u8; 9]; 9],
+    column_counts: [[u8; 10]; 9],
+    box_counts: [[u8; 10]; 9],
+    mask: [[bool; 9]; 9],
+}
+
+impl ConflictTracker {
+    pub fn new(board: &[[u8; 9]; 9]) -> Self {
+        let mut tracker = ConflictTracker {
+            board: *board,
+            column_counts: [[0; 10]; 9],
+            box_counts: [[0; 10]; 9],
+            mask: [[false; 9]; 9],
+        };
+        for row in 0..9 {
+            for col in 0..9 {
+                let value = board[row][col] as usize;
+                tracker.column_counts[col][value] += 1;
+                tracker.box_counts[box_index(row, col)][value] += 1;
+            }
+        }
+        for row in 0..9 {
+            for col in 0..9 {
+                tracker.refresh_cell(row, col);
+            }
+        }
+        tracker
+    }
+
+    pub fn board(&self) -> &[[u8; 9]; 9] {
+        &self.board
+    }
+
+    /// Same cells as `conflict_mask(self.board())`.
+    pub fn mask(&self) -> &[[bool; 9]; 9] {
+        &self.mask
+    }
+
+    /// Swaps two cells of `row` and refreshes the mask for the columns and boxes touched.
+    pub fn swap(&mut self, row: usize, col_a: usize, col_b: usize) {
+        let value_a = self.board[row][col_a] as usize;
+        let value_b = self.board[row][col_b] as usize;
+        if value_a == value_b {
+            return;
+        }
+        let (box_a, box_b) = (box_index(row, col_a), box_index(row, col_b));
+        self.column_counts[col_a][value_a] -= 1;
+        self.column_counts[col_a][value_b] += 1;
+        self.column_counts[col_b][value_b] -= 1;
+        self.column_counts[col_b][value_a] += 1;
+        self.box_counts[box_a][value_a] -= 1;
+        self.box_counts[box_a][value_b] += 1;
+        self.box_counts[box_b][value_b] -= 1;
+        self.box_counts[box_b][value_a] += 1;
+        self.board[row].swap(col_a, col_b);
+        for col in [col_a, col_b] {
+            for other_row in 0..9 {
+                self.refresh_cell(other_row, col);
+            }
+        }
+        for block in [box_a, box_b] {
+            let (top, left) = (block / 3 * 3, block % 3 * 3);
+            for other_row in top..top + 3 {
+                for other_col in left..left + 3 {
+                    self.refresh_cell(other_row, other_col);
+                }
+            }
+        }
+    }
+
+    fn refresh_cell(&mut self, row: usize, col: usize) {
+        let value = self.board[row][col] as usize;
+        self.mask[row][col] =
+            self.column_counts[col][value] > 1 || self.box_counts[box_index(row, col)][value] > 1;
+    }
+}
+
+fn box_index(row: usize, col: usize) -> usize {
+    row / 3 * 3 + col / 3
+}
+
+/// Checks every row, column, and box from scratch, independent of the sampler's energy.
+pub fn is_valid_solution(board: &[[u8; 9]; 9]) -> bool {
+    for index in 0..9 {
+        let mut row = [0u8; 9];
+        let mut column = [0u8; 9];
+        let mut block = [0u8; 9];
+        for offset in 0..9 {
+            row[offset] = board[index][offset];
+            column[offset] = board[offset][index];
+            block[offset] = board[(index / 3) * 3 + offset / 3][(index % 3) * 3 + offset % 3];
+        }
+        if !is_complete_group(&row) || !is_complete_group(&column) || !is_complete_group(&block) {
+            return false;
+        }
+    }
+    true
+}
+
+pub fn respects_givens(board: &[[u8; 9]; 9], givens: &[[Option<u8>; 9]; 9]) -> bool {
+    (0..9)
+        .all(|row| (0..9).all(|col| givens[row][col].is_none_or(|value| board[row][col] == value)))
+}
+
+/// Verdict of `check_solution` on a candidate answer.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CheckResult {
+    /// Givens the candidate changes, as `(row, col, given, candidate)`.
+    pub violated_givens: Vec<(usize, usize, u8, u8)>,
+    /// Filled cells whose digit repeats in their row, column, or box, in row-major order.
+    pub conflicts: Vec<(usize, usize)>,
+    /// Cells left empty, in row-major order.
+    pub empty: Vec<(usize, usize)>,
+}
+
+impl CheckResult {
+    /// Complete, agrees with every given, and free of conflicts.
+    pub fn is_correct(&self) -> bool {
+        self.violated_givens.is_empty() && self.conflicts.is_empty() && self.empty.is_empty()
+    }
+}
+
+/// Grades a candidate answer, separating cells that contradict the puzzle's givens from a
+/// board that is inconsistent on its own. Unlike the sampler, rows are checked too, since
+/// an external solution need not keep them as permutations. `0` marks an empty cell.
+pub fn check_solution(puzzle: &SudokuPuzzle, candidate: &[[u8; 9]; 9]) -> CheckResult {
+    let columns_and_boxes = conflict_mask(candidate);
+    let rows_and_boxes = Orientation::Columns.conflict_mask(candidate);
+    let mut result = CheckResult::default();
+    for row in 0..9 {
+        for col in 0..9 {
+            let value = candidate[row][col];
+            if let Some(given) = puzzle.givens[row][col]
+                && value != given
+            {
+                result.violated_givens.push((row, col, given, value));
+            }
+            if value == 0 {
+                result.empty.push((row, col));
+            } else if columns_and_boxes[row][col] || rows_and_boxes[row][col] {
+                result.conflicts.push((row, col));
+            }
+        }
+    }
+    result
+}
+
+/// Column and box conflicts where each duplicated group contributes `(count - 1) / count`
+/// of the summed weights of its cells, so uniform weights of 1.0 reproduce the plain energy.
+pub fn weighted_energy(board: &[[u8; 9]; 9], weights: &[[f64; 9]; 9]) -> f64 {
+    let mut energy = 0.0;
+    for col in 0..9 {
+        let cells: Vec<(usize, usize)> = (0..9).map(|row| (row, col)).collect();
+        energy += weighted_group_conflicts(board, weights, &cells);
+    }
+    for block_row in 0..3 {
+        for block_col in 0..3 {
+            let cells: Vec<(usize, usize)> = (0..9)
+                .map(|i| (block_row * 3 + i / 3, block_col * 3 + i % 3))
+                .collect();
+            energy += weighted_group_conflicts(board, weights, &cells);
+        }
+    }
+    energy
+}
+
+pub fn count_givens(givens: &[[Option<u8>; 9]; 9]) -> usize {
+    givens.iter().flatten().filter(|value| value.is_some()).count()
+}
+
+/// The first two givens (in row-major order) that repeat a digit within a row, column, or
+/// box; such a puzzle has no solution at all.
+pub fn find_given_clash(
+    givens: &[[Option<u8>; 9]; 9],
+) -> Option<((usize, usize), (usize, usize))> {
+    let cells: Vec<(usize, usize, u8)> = (0..81)
+        .filter_map(|index| givens[index / 9][index % 9].map(|value| (index / 9, index % 9, value)))
+        .collect();
+    for (position, &(row_a, col_a, value_a)) in cells.iter().enumerate() {
+        for &(row_b, col_b, value_b) in &cells[position + 1..] {
+            let shares_group = row_a == row_b
+                || col_a == col_b
+                || (row_a / 3 == row_b / 3 && col_a / 3 == col_b / 3);
+            if value_a == value_b && shares_group {
+                return Some(((row_a, col_a), (row_b, col_b)));
+            }
+        }
+    }
+    None
+}
+
+/// True when there are too few givens for the puzzle to possibly have a unique solution.
+pub fn is_underconstrained(givens: &[[Option<u8>; 9]; 9]) -> bool {
+    count_givens(givens) < MIN_UNIQUE_GIVENS
+}
+
+/// Exact backtracking solver: counts completions of `givens`, stopping once `limit` is
+/// reached. Givens that already clash yield 0.
+pub fn count_solutions(givens: &[[Option<u8>; 9]; 9], limit: usize) -> usize {
+    let mut count = 0;
+    if limit > 0
+        && let Some(mut search) = ExactSearch::new(givens)
+    {
+        search.run(&mut |_| {
+            count += 1;
+            count < limit
+        });
+    }
+    count
+}
+
+/// Placements the exact solver may need, once singles stall, for a puzzle to grade `Hard`
+/// rather than `Expert`.
+pub const EXPERT_SEARCH_PLACEMENTS: usize = 200;
+
+/// Fresh puzzles `generate_puzzle` digs before giving up.
+pub const GENERATE_ATTEMPTS: usize = 100;
+
+/// Grades a uniquely solvable puzzle by the techniques it needs: fill in naked singles, then
+/// hidden singles, for as long as either applies; if the board is still not full, count the
+/// placements the exact solver makes before it reaches the solution. `None` when the givens
+/// clash or the puzzle has no solution.
+pub fn estimate_difficulty(puzzle: &SudokuPuzzle) -> Option<Difficulty> {
+    let mut search = ExactSearch::new(&puzzle.givens)?;
+    let mut hidden = false;
+    loop {
+        if search.place_naked_singles()? {
+            continue;
+        }
+        if search.place_hidden_single() {
+            hidden = true;
+            continue;
+        }
+        break;
+    }
+    if search.most_constrained().is_none() {
+        return Some(if hidden {
+            Difficulty::Medium
+        } else {
+            Difficulty::Easy
+        });
+    }
+    let before = search.placements;
+    if search.run(&mut |_| false) {
+        return None;
+    }
+    Some(if search.placements - before <= EXPERT_SEARCH_PLACEMENTS {
+        Difficulty::Hard
+    } else {
+        Difficulty::Expert
+    })
+}
+
+/// A uniquely solvable puzzle graded `difficulty`. Each attempt starts from a fresh solved
+/// grid and tries to empty every cell once, in random order, keeping a removal only if the
+/// solution stays unique and the grade does not exceed `difficulty`. The attempt succeeds if
+/// the dug-out puzzle ends up graded exactly `difficulty`. `None` after `GENERATE_ATTEMPTS`
+/// failed attempts.
+pub fn generate_puzzle<R: Rng + ?Sized>(
+    difficulty: Difficulty,
+    rng: &mut R,
+) -> Option<SudokuPuzzle> {
+    (0..GENERATE_ATTEMPTS).find_map(|_| {
+        let mut puzzle = SudokuPuzzle {
+            givens: solved_givens(rng),
+        };
+        let mut cells: Vec<(usize, usize)> = (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .collect();
+        cells.shuffle(rng);
+        for (row, col) in cells {
+            let given = puzzle.givens[row][col].take();
+            if count_solutions(&puzzle.givens, 2) != 1
+                || estimate_difficulty(&puzzle).is_none_or(|grade| grade > difficulty)
+            {
+                puzzle.givens[row][col] = given;
+            }
+        }
+        (estimate_difficulty(&puzzle) == Some(difficulty)).then_some(puzzle)
+    })
+}
+
+/// Every completion of `puzzle`, found lazily by the exact backtracking solver in the same
+/// order `count_solutions` visits them. Only the search path is kept between items, never
+/// the solutions already yielded. Givens that already clash yield nothing.
+pub fn completions_iter(puzzle: &SudokuPuzzle) -> impl Iterator<Item = SudokuState> {
+    Completions {
+        search: ExactSearch::new(&puzzle.givens),
+        stack: Vec::new(),
+        started: false,
+    }
+}
+
+/// `ExactSearch::run` unrolled into an explicit stack of `(row, col, digit placed)` frames,
+/// so the search can pause after each completion.
+struct Completions {
+    search: Option<ExactSearch>,
+    stack: Vec<(usize, usize, u8)>,
+    started: bool,
+}
+
+impl Iterator for Completions {
+    type Item = SudokuState;
+
+    fn next(&mut self) -> Option<SudokuState> {
+        let search = self.search.as_mut()?;
+        if !self.started {
+            self.started = true;
+            match search.most_constrained() {
+                Some((row, col)) => self.stack.push((row, col, 0)),
+                None => {
+                    let board = search.board;
+                    self.search = None;
+                    return Some(SudokuState { board });
+                }
+            }
+        }
+        while let Some((row, col, last)) = self.stack.pop() {
+            if last != 0 {
+                search.clear(row, col);
+            }
+            let used = search.used(row, col);
+            if let Some(value) = (last + 1..=9).find(|&value| used & (1 << value) == 0) {
+                search.place(row, col, value);
+                self.stack.push((row, col, value));
+                match search.most_constrained() {
+                    Some((next_row, next_col)) => self.stack.push((next_row, next_col, 0)),
+                    None => return Some(SudokuState { board: search.board }),
+                }
+            }
+        }
+        self.search = None;
+        None
+    }
+}
+
+/// Bitmask backtracking over the empty cells; bit `d` of a mask marks digit `d` as used.
+struct ExactSearch {
+    board: [[u8; 9]; 9],
+    rows: [u16; 9],
+    cols: [u16; 9],
+    boxes: [u16; 9],
+    /// Digits placed so far, givens included; backtracking does not decrease it.
+    placements: usize,
+}
+
+impl ExactSearch {
+    fn new(givens: &[[Option<u8>; 9]; 9]) -> Option<Self> {
+        let mut search = ExactSearch {
+            board: [[0; 9]; 9],
+            rows: [0; 9],
+            cols: [0; 9],
+            boxes: [0; 9],
+            placements: 0,
+        };
+        for row in 0..9 {
+            for col in 0..9 {
+                if let Some(value) = givens[row][col] {
+                    if search.used(row, col) & (1 << value) != 0 {
+                        return None;
+                    }
+                    search.place(row, col, value);
+                }
+            }
+        }
+        Some(search)
+    }
+
+    fn used(&self, row: usize, col: usize) -> u16 {
+        self.rows[row] | self.cols[col] | self.boxes[(row / 3) * 3 + col / 3]
+    }
+
+    fn place(&mut self, row: usize, col: usize, value: u8) {
+        self.placements += 1;
+        self.board[row][col] = value;
+        self.rows[row] |= 1 << value;
+        self.cols[col] |= 1 << value;
+        self.boxes[(row / 3) * 3 + col / 3] |= 1 << value;
+    }
+
+    fn clear(&mut self, row: usize, col: usize) {
+        let bit = !(1u16 << self.board[row][col]);
+        self.board[row][col] = 0;
+        self.rows[row] &= bit;
+        self.cols[col] &= bit;
+        self.boxes[(row / 3) * 3 + col / 3] &= bit;
+    }
+
+    /// The empty cell with the fewest candidate digits, or `None` once the board is full.
+    fn most_constrained(&self) -> Option<(usize, usize)> {
+        let mut target = None;
+        let mut fewest = 10;
+        for row in 0..9 {
+            for col in 0..9 {
+                if self.board[row][col] == 0 {
+                    let options = 9 - (self.used(row, col) >> 1).count_ones();
+                    if options < fewest {
+                        fewest = options;
+                        target = Some((row, col));
+                    }
+                }
+            }
+        }
+        target
+    }
+
+    /// Fills every empty cell that has exactly one candidate, in one sweep. `Some(true)` if
+    /// any was filled; `None` if some empty cell has no candidate left.
+    fn place_naked_singles(&mut self) -> Option<bool> {
+        let mut placed = false;
+        for row in 0..9 {
+            for col in 0..9 {
+                if self.board[row][col] != 0 {
+                    continue;
+                }
+                let candidates = !self.used(row, col) & 0b11_1111_1110;
+                match candidates.count_ones() {
+                    0 => return None,
+                    1 => {
+                        self.place(row, col, candidates.trailing_zeros() as u8);
+                        placed = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Some(placed)
+    }
+
+    /// Fills the first cell found that is the only place left for some digit in its row,
+    /// column, or box. Returns whether one was filled.
+    fn place_hidden_single(&mut self) -> bool {
+        let units = (0..9).flat_map(|index| {
+            [
+                (0..9).map(|col| (index, col)).collect::<Vec<_>>(),
+                (0..9).map(|row| (row, index)).collect(),
+                (0..9)
+                    .map(|cell| ((index / 3) * 3 + cell / 3, (index % 3) * 3 + cell % 3))
+                    .collect(),
+            ]
+        });
+        for unit in units {
+            for value in 1..=9u8 {
+                let mut spots = unit.iter().filter(|&&(row, col)| {
+                    self.board[row][col] == 0 && self.used(row, col) & (1 << value) == 0
+                });
+                if let (Some(&(row, col)), None) = (spots.next(), spots.next()) {
+                    self.place(row, col, value);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Visits every completion; `visit` returns `false` to stop early. Returns `false` if stopped.
+    fn run(&mut self, visit: &mut dyn FnMut(&[[u8; 9]; 9]) -> bool) -> bool {
+        let Some((row, col)) = self.most_constrained() else {
+            return visit(&self.board);
+        };
+        let used = self.used(row, col);
+        for value in 1..=9u8 {
+            if used & (1 << value) == 0 {
+                self.place(row, col, value);
+                let keep_going = self.run(visit);
+                self.clear(row, col);
+                if !keep_going {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+fn weighted_group_conflicts(
+    board: &[[u8; 9]; 9],
+    weights: &[[f64; 9]; 9],
+    cells: &[(usize, usize)],
+) -> f64 {
+    let mut counts = [0usize; 10];
+    let mut totals = [0.0; 10];
+    for &(row, col) in cells {
+        let value = board[row][col] as usize;
+        counts[value] += 1;
+        totals[value] += weights[row][col];
+    }
+    let mut conflicts = 0.0;
+    for value in 1..=9 {
+        if counts[value] > 1 {
+            conflicts += totals[value] * (counts[value] - 1) as f64 / counts[value] as f64;
+        }
+    }
+    conflicts
+}
+
+fn is_complete_group(values: &[u8; 9]) -> bool {
+    let mut seen = [false; 10];
+    for &value in values {
+        let value = value as usize;
+        if !(1..=9).contains(&value) || seen[value] {
+            return false;
+        }
+        seen[value] = true;
+    }
+    true
+}
+
+fn row_conflicts(board: &[[u8; 9]; 9]) -> usize {
+    let mut conflicts = 0;
+    for row in 0..9 {
+        let mut counts = [0u8; 10];
+        for col in 0..9 {
+            counts[board[row][col] as usize] += 1;
+        }
+        conflicts += duplicates(&counts);
+    }
+    conflicts
+}
+
+fn column_conflicts(board: &[[u8; 9]; 9]) -> usize {
+    (0..9).map(|col| column_energy(board, col)).sum()
+}
+
+fn box_conflicts(board: &[[u8; 9]; 9]) -> usize {
+    let mut conflicts = 0;
+    for block_row in 0..3 {
+        for block_col in 0..3 {
+            conflicts += box_energy(board, block_row, block_col);
+        }
+    }
+    conflicts
+}
+
+/// Conflicts in each column and each 3×3 box (indexed `[block_row][block_col]`), for
+/// heat-mapping the hottest regions. The totals sum to the column plus box energy.
+pub fn region_energies(board: &[[u8; 9]; 9]) -> ([usize; 9], [[usize; 3]; 3]) {
+    let mut columns = [0; 9];
+    for col in 0..9 {
+        columns[col] = column_energy(board, col);
+    }
+    let mut boxes = [[0; 3]; 3];
+    for block_row in 0..3 {
+        for block_col in 0..3 {
+            boxes[block_row][block_col] = box_energy(board, block_row, block_col);
+        }
+    }
+    (columns, boxes)
+}
+
+/// The sampler's energy split by constraint type, so a board that will not settle shows which
+/// constraint is hardest to satisfy. The parts sum to the energy under the same config.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EnergyBreakdown {
+    /// `None` when columns are not part of the energy: under `Orientation::Columns` without
+    /// `include_row_conflicts`.
+    pub columns: Option<usize>,
+    pub boxes: usize,
+    /// `None` when rows are not part of the energy: under `Orientation::Rows` without
+    /// `include_row_conflicts`.
+    pub rows: Option<usize>,
+}
+
+impl EnergyBreakdown {
+    pub fn total(&self) -> usize {
+        self.columns.unwrap_or(0) + self.boxes + self.rows.unwrap_or(0)
+    }
+
+    /// `(constraint, conflicts)` pairs in reporting order, for the constraints that count.
+    pub fn parts(&self) -> Vec<(&'static str, usize)> {
+        [
+            ("columns", self.columns),
+            ("boxes", Some(self.boxes)),
+            ("rows", self.rows),
+        ]
+        .into_iter()
+        .filter_map(|(constraint, conflicts)| Some((constraint, conflicts?)))
+        .collect()
+    }
+}
+
+pub fn energy_breakdown(board: &[[u8; 9]; 9], config: &SamplerConfig) -> EnergyBreakdown {
+    let (rows, columns) = match config.orientation {
+        Orientation::Rows => (config.include_row_conflicts, true),
+        Orientation::Columns => (true, config.include_row_conflicts),
+    };
+    EnergyBreakdown {
+        columns: columns.then(|| column_conflicts(board)),
+        boxes: box_conflicts(board),
+        rows: rows.then(|| row_conflicts(board)),
+    }
+}
+
+fn column_energy(board: &[[u8; 9]; 9], col: usize) -> usize {
+    let mut counts = [0u8; 10];
+    for row in 0..9 {
+        counts[board[row][col] as usize] += 1;
+    }
+    duplicates(&counts)
+}
+
+fn box_energy(board: &[[u8; 9]; 9], block_row: usize, block_col: usize) -> usize {
+    let mut counts = [0u8; 10];
+    for row in (block_row * 3)..(block_row * 3 + 3) {
+        for col in (block_col * 3)..(block_col * 3 + 3) {
+            counts[board[row][col] as usize] += 1;
+        }
+    }
+    duplicates(&counts)
+}
+
+/// Extra occurrences of each digit 1-9; index 0 (an empty cell) never conflicts.
+fn duplicates(counts: &[u8; 10]) -> usize {
+    counts
+        .iter()
+        .skip(1)
+        .map(|&count| count.saturating_sub(1) as usize)
+        .sum()
+}
+
+fn solved_givens<R: Rng + ?Sized>(rng: &mut R) -> [[Option<u8>; 9]; 9] {
+    let solution = generate_full_solution(rng);
+    let mut givens = [[None; 9]; 9];
+    for row in 0..9 {
+        for col in 0..9 {
+            givens[row][col] = Some(solution[row][col]);
+        }
+    }
+    givens
+}
+
+fn generate_full_solution<R: Rng + ?Sized>(rng: &mut R) -> [[u8; 9]; 9] {
+    let mut row_bands: Vec<usize> = (0..3).collect();
+    row_bands.shuffle(rng);
+    let mut rows = Vec::with_capacity(9);
+    for &band in &row_bands {
+        let mut offsets = vec![0, 1, 2];
+        offsets.shuffle(rng);
+        for offset in offsets {
+            rows.push(band * 3 + offset);
+        }
+    }
+
+    let mut col_bands: Vec<usize> = (0..3).collect();
+    col_bands.shuffle(rng);
+    let mut cols = Vec::with_capacity(9);
+    for &band in &col_bands {
+        let mut offsets = vec![0, 1, 2];
+        offsets.shuffle(rng);
+        for offset in offsets {
+            cols.push(band * 3 + offset);
+        }
+    }
+
+    let mut nums: Vec<u8> = (1..=9).collect();
+    nums.shuffle(rng);
+
+    let mut board = [[0u8; 9]; 9];
+    for (i, &row) in rows.iter().enumerate() {
+        for (j, &col) in cols.iter().enumerate() {
+            board[i][j] = nums[pattern(row, col)];
+        }
+    }
+    board
+}
+
+fn pattern(row: usize, col: usize) -> usize {
+    (3 * (row % 3) + row / 3 + col) % 9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{Cooling, ScheduleSpec};
+
+    fn puzzle(seed: u64) -> SudokuPuzzle {
+        SudokuPuzzle::reproduce(seed, 45)
+    }
+
+    /// `cooling` from the default starting temperature.
+    fn from_default_start(cooling: Cooling) -> CoolingSchedule {
+        CoolingSchedule {
+            start_temp: DEFAULT_START_TEMP,
+            cooling,
+        }
+    }
+
+    /// Drives `steps` proposals that actually swap something.
+    fn run_swaps(
+        anneal: &mut AnnealState,
+        puzzle: &SudokuPuzzle,
+        config: &SamplerConfig,
+        steps: usize,
+    ) {
+        let free = puzzle.row_free_positions();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut swaps = 0;
+        while swaps < steps {
+            if anneal_step(anneal, &free, config, &mut rng).swap.is_some() {
+                swaps += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn reheat_restarts_every_schedule_from_the_scaled_start() {
+        let puzzle = puzzle(3);
+        let mut schedules: Vec<CoolingSchedule> = ScheduleSpec::ALL
+            .iter()
+            .map(|spec| spec.schedule(DEFAULT_START_TEMP, DEFAULT_COOLING_RATE, 1000))
+            .collect();
+        schedules.push(CoolingSchedule {
+            start_temp: DEFAULT_START_TEMP,
+            cooling: Cooling::Explicit(vec![2.0, 1.5, 1.0, 0.5]),
+        });
+        for schedule in schedules {
+            let config = SamplerConfig {
+                max_steps: 1000,
+                temperature_floor: 0.0,
+                schedule: schedule.clone(),
+                ..SamplerConfig::default()
+            };
+            let mut rng = StdRng::seed_from_u64(2);
+            let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+            run_swaps(&mut anneal, &puzzle, &config, 200);
+            let board = anneal.state.board;
+            let origin = anneal.steps;
+            anneal.reheat(&config, 1.5);
+
+            let expected = match &schedule.cooling {
+                Cooling::Explicit(temperatures) => temperatures[0] * 1.5,
+                Cooling::Reverse { low, .. } => *low,
+                _ => DEFAULT_START_TEMP * 1.5,
+            };
+            assert_eq!(anneal.temperature, expected, "{schedule}");
+            assert_eq!(anneal.state.board, board, "{schedule} reset the board");
+
+            let before = anneal.temperature;
+            run_swaps(&mut anneal, &puzzle, &config, 1);
+            let step = anneal.steps - origin;
+            let expected = match &schedule.cooling {
+                Cooling::Explicit(temperatures) => temperatures[step] * 1.5,
+                _ => schedule.reheated(1.5, config.max_steps - origin).next_temp(step, before),
+            };
+            assert!((anneal.temperature - expected).abs() < 1e-12, "{schedule}");
+        }
+    }
+
+    #[test]
+    fn linear_schedule_stays_reheated_after_a_reheat() {
+        let puzzle = puzzle(3);
+        let config = SamplerConfig {
+            max_steps: 1000,
+            schedule: from_default_start(Cooling::Linear {
+                step: DEFAULT_START_TEMP / 1000.0,
+            }),
+            ..SamplerConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+        run_swaps(&mut anneal, &puzzle, &config, 900);
+        let cold = anneal.temperature;
+        anneal.reheat(&config, 1.0);
+        run_swaps(&mut anneal, &puzzle, &config, 10);
+        assert!(anneal.temperature > 2.0 && anneal.temperature > cold);
+    }
+
+    #[test]
+    fn solve_reheats_and_snapshots_every_cooling_pass() {
+        let config = SamplerConfig {
+            max_steps: 20_000,
+            schedule: CoolingSchedule::geometric(2.0, 0.99),
+            temperature_floor: 0.05,
+            snapshot_decades: true,
+            stagnation_window: Some(2000),
+            ..SamplerConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        let (_, stats) = solve(&SudokuPuzzle::reproduce(3, 60), &config, &mut rng);
+        assert!(stats.reheats > 0);
+        let passes = stats.snapshots.iter().filter(|snapshot| snapshot.decade == 1.0).count();
+        assert_eq!(passes, stats.reheats + 1);
+    }
+
+    #[test]
+    fn doubling_a_region_weight_raises_its_energy_share() {
+        let mut board = generate_full_solution(&mut StdRng::seed_from_u64(4));
+        board[0].swap(0, 4);
+        let uniform = [[1.0; 9]; 9];
+        assert_eq!(weighted_energy(&board, &uniform), board_energy(&board) as f64);
+
+        let mut doubled = uniform;
+        for row in doubled.iter_mut().take(3) {
+            row[..3].fill(2.0);
+        }
+        assert!(weighted_energy(&board, &doubled) > weighted_energy(&board, &uniform));
+        // A box without conflicts contributes nothing, however heavy.
+        let mut far = uniform;
+        far[8][8] = 2.0;
+        assert_eq!(weighted_energy(&board, &far), weighted_energy(&board, &uniform));
+    }
+
+    #[test]
+    fn zero_energy_is_best_even_when_weights_score_it_no_lower() {
+        let solution = generate_full_solution(&mut StdRng::seed_from_u64(5));
+        let mut givens = solution.map(|row| row.map(Some));
+        givens[0][0] = None;
+        givens[0][1] = None;
+        let mut start = solution;
+        start[0].swap(0, 1);
+        let config = SamplerConfig {
+            cell_weights: Some([[0.0; 9]; 9]),
+            ..SamplerConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(6);
+        let (best, stats) = solve_from(
+            &SudokuPuzzle { givens },
+            SudokuState { board: start },
+            &config,
+            &mut rng,
+        );
+        assert_eq!(stats.best_energy, 0);
+        assert_eq!(best.board, solution);
+    }
+
+    #[test]
+    fn is_valid_solution_accepts_solutions_and_rejects_broken_boards() {
+        let solution = generate_full_solution(&mut StdRng::seed_from_u64(9));
+        assert!(is_valid_solution(&solution));
+
+        let mut swapped = solution;
+        swapped[2].swap(0, 5);
+        assert!(!is_valid_solution(&swapped));
+        let mut empty_cell = solution;
+        empty_cell[4][4] = 0;
+        assert!(!is_valid_solution(&empty_cell));
+        // Valid rows and columns are not enough when the boxes repeat digits.
+        let latin: [[u8; 9]; 9] =
+            std::array::from_fn(|row| std::array::from_fn(|col| ((row + col) % 9 + 1) as u8));
+        assert!(!is_valid_solution(&latin));
+    }
+
+    #[test]
+    fn one_decade_snapshot_per_power_of_ten_crossed() {
+        let config = SamplerConfig {
+            max_steps: 3000,
+            schedule: CoolingSchedule::geometric(DEFAULT_START_TEMP, 0.995),
+            temperature_floor: 1e-6,
+            snapshot_decades: true,
+            ..SamplerConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(10);
+        let (_, stats) = solve(&SudokuPuzzle::reproduce(10, 60), &config, &mut rng);
+        // 2.4 * 0.995^3000 is about 7e-7: the floor stops it at 1e-6, crossing 1 .. 1e-5.
+        let crossed = -(stats.temperature.log10().floor() as i32);
+        assert_eq!(stats.snapshots.len(), crossed as usize);
+        assert_eq!((stats.steps, stats.snapshots.len()), (3000, 6));
+        for (k, snapshot) in stats.snapshots.iter().enumerate() {
+            assert_eq!(snapshot.decade, 10f64.powi(-(k as i32)));
+            assert!(snapshot.temperature < snapshot.decade);
+        }
+    }
+
+    #[test]
+    fn best_single_move_finds_the_swap_that_solves_the_board() {
+        let solution = generate_full_solution(&mut StdRng::seed_from_u64(11));
+        let mut board = solution;
+        board[6].swap(2, 7);
+        let state = SudokuState { board };
+        assert_eq!(best_single_move(&state, &[[None; 9]; 9]), Some((6, 2, 7)));
+    }
+
+    #[test]
+    fn best_single_move_is_none_without_an_improving_swap() {
+        let state = SudokuState {
+            board: generate_full_solution(&mut StdRng::seed_from_u64(8)),
+        };
+        assert_eq!(best_single_move(&state, &[[None; 9]; 9]), None);
+    }
+
+    #[test]
+    fn a_pinned_chacha8_seed_reproduces_the_solve() {
+        use rand_chacha::ChaCha8Rng;
+
+        let config = SamplerConfig {
+            max_steps: 20_000,
+            trace_every: Some(50),
+            ..SamplerConfig::default()
+        };
+        let run = || {
+            let mut rng = ChaCha8Rng::seed_from_u64(12);
+            let puzzle = SudokuPuzzle::with_random_holes(45, &mut rng);
+            let (state, stats) = solve(&puzzle, &config, &mut rng);
+            let trace: Vec<(usize, usize)> =
+                stats.trace.iter().map(|point| (point.step, point.energy)).collect();
+            (puzzle.givens, state.board, stats.steps, trace)
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn uphill_cap_of_one_never_accepts_two_uphill_swaps_in_a_row() {
+        let puzzle = puzzle(13);
+        let config = SamplerConfig {
+            schedule: CoolingSchedule::geometric(100.0, 0.9999),
+            max_consecutive_uphill: Some(1),
+            ..SamplerConfig::default()
+        };
+        let free = puzzle.row_free_positions();
+        let mut rng = StdRng::seed_from_u64(13);
+        let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+        let (mut previous_uphill, mut uphill_accepted) = (false, 0);
+        for _ in 0..5000 {
+            let outcome = anneal_step(&mut anneal, &free, &config, &mut rng);
+            if outcome.swap.is_none() {
+                continue;
+            }
+            let uphill = outcome.accepted && outcome.delta > 0.0;
+            assert!(!(previous_uphill && uphill), "two uphill swaps in a row");
+            previous_uphill = uphill;
+            uphill_accepted += usize::from(uphill);
+        }
+        assert!(uphill_accepted > 100);
+    }
+
+    #[test]
+    fn fewer_than_17_givens_is_underconstrained() {
+        assert_eq!(clamp_holes(65, false), 81 - MIN_UNIQUE_GIVENS);
+        assert_eq!(clamp_holes(65, true), 65);
+        assert_eq!(clamp_holes(3, true), MIN_GENERATED_HOLES);
+        let mut rng = StdRng::seed_from_u64(14);
+        assert!(is_underconstrained(&SudokuPuzzle::with_random_holes(65, &mut rng).givens));
+        assert!(!is_underconstrained(&SudokuPuzzle::with_random_holes(64, &mut rng).givens));
+    }
+
+    #[test]
+    fn trace_every_keeps_every_nth_accepted_swap_plus_the_final_state() {
+        let trace = |every| {
+            let config = SamplerConfig {
+                max_steps: 20_000,
+                trace_every: Some(every),
+                ..SamplerConfig::default()
+            };
+            let mut rng = StdRng::seed_from_u64(15);
+            let (_, stats) = solve(&SudokuPuzzle::reproduce(15, 55), &config, &mut rng);
+            (stats.trace, stats.steps)
+        };
+        let (every_swap, steps) = trace(1);
+        let (sampled, _) = trace(100);
+        let accepted = every_swap.len() - usize::from(every_swap.last().unwrap().step != steps);
+        let last_kept = (accepted >= 100).then(|| every_swap[accepted / 100 * 100 - 1].step);
+        assert_eq!(sampled.len(), accepted / 100 + usize::from(last_kept != Some(steps)));
+        for (k, point) in sampled.iter().take(accepted / 100).enumerate() {
+            assert_eq!(point.step, every_swap[k * 100 + 99].step);
+        }
+        assert_eq!(sampled.last().unwrap().step, steps);
+    }
+
+    #[test]
+    fn zero_lateral_probability_rejects_every_plateau_swap() {
+        let puzzle = puzzle(16);
+        let config = SamplerConfig {
+            lateral_accept_prob: 0.0,
+            ..SamplerConfig::default()
+        };
+        let free = puzzle.row_free_positions();
+        let mut rng = StdRng::seed_from_u64(16);
+        let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+        let mut plateau_swaps = 0;
+        for _ in 0..5000 {
+            let outcome = anneal_step(&mut anneal, &free, &config, &mut rng);
+            if outcome.swap.is_some() && outcome.delta == 0.0 {
+                assert!(!outcome.accepted);
+                plateau_swaps += 1;
+            }
+        }
+        assert!(plateau_swaps > 0);
+    }
+
+    #[test]
+    fn row_duplicates_count_only_with_the_row_term() {
+        let mut board = generate_full_solution(&mut StdRng::seed_from_u64(17));
+        // Overwrite one cell with its row neighbour: a row, column, and box duplicate.
+        board[3][3] = board[3][4];
+        let state = SudokuState { board };
+        let with_rows = SamplerConfig {
+            include_row_conflicts: true,
+            ..SamplerConfig::default()
+        };
+        let without = config_energy(&state, &SamplerConfig::default());
+        assert_eq!(config_energy(&state, &with_rows), without + 1);
+        assert_eq!(row_conflicts(&generate_full_solution(&mut StdRng::seed_from_u64(17))), 0);
+    }
+
+    #[test]
+    fn aggressive_cooling_reaches_the_floor_early() {
+        let config = SamplerConfig {
+            max_steps: 2000,
+            schedule: CoolingSchedule::geometric(DEFAULT_START_TEMP, 0.8),
+            ..SamplerConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(18);
+        let (_, stats) = solve(&SudokuPuzzle::reproduce(18, 60), &config, &mut rng);
+        // 2.4 · 0.8^11 < 0.25, so eleven swaps reach the floor (plus any rows with no swap).
+        let reached = stats.floor_reached_at.unwrap();
+        assert!((11..30).contains(&reached), "{reached}");
+        assert_eq!(stats.temperature, DEFAULT_TEMPERATURE_FLOOR);
+
+        let gentle = SamplerConfig {
+            max_steps: 2000,
+            ..SamplerConfig::default()
+        };
+        let (_, stats) = solve(&SudokuPuzzle::reproduce(18, 60), &gentle, &mut rng);
+        assert_eq!(stats.floor_reached_at, None);
+    }
+
+    #[test]
+    fn region_energies_sum_to_column_and_box_conflicts() {
+        for seed in 0..5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let board = puzzle(seed).random_initial_state(&mut rng).board;
+            let (columns, boxes) = region_energies(&board);
+            let total = columns.iter().sum::<usize>() + boxes.iter().flatten().sum::<usize>();
+            assert_eq!(total, column_conflicts(&board) + box_conflicts(&board));
+            assert!(total > 0);
+        }
+        let solved = generate_full_solution(&mut StdRng::seed_from_u64(1));
+        assert_eq!(region_energies(&solved), ([0; 9], [[0; 3]; 3]));
+    }
+
+    #[test]
+    fn rotational_symmetry_hits_the_exact_hole_count() {
+        for holes in [40, 41, 81] {
+            let mut rng = StdRng::seed_from_u64(holes as u64);
+            let puzzle = SudokuPuzzle::with_symmetric_holes(holes, Symmetry::Rotational, &mut rng);
+            assert_eq!(81 - count_givens(&puzzle.givens), holes);
+            assert_eq!(puzzle.givens[4][4].is_none(), holes % 2 == 1);
+            for (row, col) in (0..9).flat_map(|row| (0..9).map(move |col| (row, col))) {
+                let mirror = puzzle.givens[8 - row][8 - col];
+                assert_eq!(puzzle.givens[row][col].is_none(), mirror.is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn single_stepping_reproduces_solve() {
+        let puzzle = puzzle(5);
+        let config = SamplerConfig::default();
+        let (solution, stats) = solve(&puzzle, &config, &mut StdRng::seed_from_u64(5));
+        assert_eq!(stats.best_energy, 0);
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+        let free = puzzle.row_free_positions();
+        while anneal.energy != 0 && anneal.steps < config.max_steps {
+            anneal_step(&mut anneal, &free, &config, &mut rng);
+        }
+        assert_eq!(anneal.state.board, solution.board);
+        assert_eq!(anneal.steps, stats.steps);
+    }
+
+    #[test]
+    fn board_energy_matches_the_state_energy() {
+        let solved = generate_full_solution(&mut StdRng::seed_from_u64(2));
+        assert_eq!(board_energy(&solved), 0);
+        for seed in 0..5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let state = puzzle(seed).random_initial_state(&mut rng);
+            assert_eq!(board_energy(&state.board), state.energy());
+        }
+        let mut board = solved;
+        board[0].swap(0, 1);
+        let state = SudokuState { board };
+        assert_eq!(board_energy(&board), state.energy());
+        assert!(state.energy() > 0);
+    }
+
+    #[test]
+    fn conflict_tracker_mask_matches_a_fresh_mask_after_swaps() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let board = puzzle(9).random_initial_state(&mut rng).board;
+        let mut tracker = ConflictTracker::new(&board);
+        assert_eq!(*tracker.mask(), conflict_mask(&board));
+        for _ in 0..500 {
+            let row = rng.random_range(0..9);
+            let (col_a, col_b) = (rng.random_range(0..9), rng.random_range(0..9));
+            tracker.swap(row, col_a, col_b);
+            assert_eq!(*tracker.mask(), conflict_mask(tracker.board()));
+        }
+        let mut expected = board;
+        expected[0].swap(1, 2);
+        let mut tracker = ConflictTracker::new(&board);
+        tracker.swap(0, 1, 2);
+        assert_eq!(*tracker.board(), expected);
+    }
+
+    #[test]
+    fn adaptive_schedule_holds_acceptance_near_its_band() {
+        let (target, band) = (0.3, 0.05);
+        let config = SamplerConfig {
+            schedule: from_default_start(Cooling::Adaptive {
+                target,
+                band,
+                rate: DEFAULT_COOLING_RATE,
+            }),
+            temperature_floor: 0.0,
+            ..SamplerConfig::default()
+        };
+        let puzzle = SudokuPuzzle::reproduce(11, 60);
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+        let free = puzzle.row_free_positions();
+        // Acceptance over consecutive windows after warm-up.
+        let mut steered = Vec::new();
+        while anneal.steps < 40_000 && anneal.energy != 0 {
+            anneal_step(&mut anneal, &free, &config, &mut rng);
+            if anneal.steps > 10_000 && anneal.steps.is_multiple_of(ACCEPTANCE_WINDOW) {
+                steered.extend(anneal.acceptance_ratio());
+            }
+        }
+        assert_eq!(steered.len(), 60);
+        // The window lags the controller, so single windows swing around the band.
+        let mean = steered.iter().sum::<f64>() / steered.len() as f64;
+        assert!((mean - target).abs() <= 2.0 * band, "{mean}");
+        assert!(steered.iter().all(|&ratio| ratio > 0.0 && ratio < target + 2.0 * band));
+    }
+
+    #[test]
+    fn free_positions_are_exactly_the_holes() {
+        let mut rng = StdRng::seed_from_u64(12);
+        for holes in [0, 1, 48, 81] {
+            let puzzle = SudokuPuzzle::with_random_holes(holes, &mut rng);
+            let free = puzzle.free_positions();
+            assert_eq!(puzzle.free_count(), holes);
+            assert_eq!(free.len(), holes);
+            assert!(free.iter().all(|&(row, col)| puzzle.givens[row][col].is_none()));
+            assert!(free.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn explicit_schedule_sets_each_steps_temperature() {
+        let temperatures = vec![3.0, 2.5, 0.4, 1.0, 0.1];
+        let config = SamplerConfig {
+            schedule: from_default_start(Cooling::Explicit(temperatures.clone())),
+            temperature_floor: 0.0,
+            ..SamplerConfig::default()
+        };
+        let puzzle = puzzle(13);
+        let mut rng = StdRng::seed_from_u64(13);
+        let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+        assert_eq!(anneal.temperature, 3.0);
+        let free = puzzle.row_free_positions();
+        let mut seen = Vec::new();
+        while seen.len() < 8 {
+            let step = anneal.steps;
+            let outcome = anneal_step(&mut anneal, &free, &config, &mut rng);
+            if outcome.swap.is_some() {
+                seen.push(outcome.temperature);
+                assert_eq!(anneal.steps, step + 1);
+            }
+        }
+        assert_eq!(seen, [3.0, 2.5, 0.4, 1.0, 0.1, 0.1, 0.1, 0.1]);
+    }
+
+    #[test]
+    fn solvable_generation_returns_a_puzzle_its_config_solves() {
+        let config = SamplerConfig {
+            max_steps: 8000,
+            ..SamplerConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(14);
+        let found = SudokuPuzzle::with_random_holes_solvable(48, &config, 20, &mut rng).unwrap();
+        assert!((1..=20).contains(&found.attempts));
+        assert_eq!(found.stats.best_energy, 0);
+        assert_eq!(found.puzzle.free_count(), 48);
+        assert_eq!(board_energy(&found.solution.board), 0);
+        assert!(respects_givens(&found.solution.board, &found.puzzle.givens));
+
+        let starved = SamplerConfig {
+            max_steps: 10,
+            ..SamplerConfig::default()
+        };
+        assert!(SudokuPuzzle::with_random_holes_solvable(48, &starved, 3, &mut rng).is_none());
+    }
+
+    #[test]
+    fn ranked_conflicts_put_the_triple_first() {
+        // Copy the top-left digit down column 0 and the top-right digit one row down column 8.
+        // Each copy stays in the original's box, so no other column or box gains a repeat.
+        let mut board = generate_full_solution(&mut StdRng::seed_from_u64(15));
+        board[1][0] = board[0][0];
+        board[2][0] = board[0][0];
+        board[1][8] = board[0][8];
+        let ranked = conflicts_ranked(&board);
+        assert_eq!(
+            ranked,
+            vec![((0, 0), 2), ((1, 0), 2), ((2, 0), 2), ((0, 8), 1), ((1, 8), 1)]
+        );
+    }
+
+    #[test]
+    fn threshold_closure_decides_every_uphill_move() {
+        let mut threshold = |delta: f64, _: f64, _: &mut StdRng| delta <= 2.0;
+        let config = SamplerConfig::default();
+        let puzzle = puzzle(18);
+        let mut rng = StdRng::seed_from_u64(18);
+        let mut anneal = AnnealState::new(puzzle.random_initial_state(&mut rng), &config);
+        let free = puzzle.row_free_positions();
+        let mut uphill = 0;
+        for _ in 0..2000 {
+            let outcome = anneal_step_with(&mut anneal, &free, &config, &mut rng, &mut threshold);
+            if outcome.delta > 0.0 {
+                assert_eq!(outcome.accepted, outcome.delta <= 2.0, "{}", outcome.delta);
+                uphill += 1;
+            }
+        }
+        assert!(uphill > 100);
+
+        // Rejecting every uphill move makes the recorded energy non-increasing.
+        let config = SamplerConfig {
+            trace_every: Some(1),
+            ..SamplerConfig::default()
+        };
+        let never = |_: f64, _: f64, _: &mut StdRng| false;
+        let run = || solve_with_acceptance(&puzzle, &config, &mut StdRng::seed_from_u64(19), never);
+        let (first, second) = (run().1, run().1);
+        assert!(first.trace.windows(2).all(|pair| pair[1].energy <= pair[0].energy));
+        assert_eq!((first.steps, first.best_energy), (second.steps, second.best_energy));
+    }
+
+    #[test]
+    fn deterministic_acceptance_rules_solve_an_easy_puzzle() {
+        let puzzle = SudokuPuzzle::reproduce(2, 30);
+        for acceptance in AcceptanceRule::ALL {
+            let config = SamplerConfig {
+                acceptance,
+                ..SamplerConfig::default()
+            };
+            let (solution, stats) = solve(&puzzle, &config, &mut StdRng::seed_from_u64(2));
+            assert_eq!(stats.best_energy, 0, "{acceptance}");
+            assert!(respects_givens(&solution.board, &puzzle.givens));
+        }
+    }
+
+    #[test]
+    fn reverse_annealing_repairs_a_one_swap_start() {
+        let solved = generate_full_solution(&mut StdRng::seed_from_u64(20));
+        let mut givens = [[None; 9]; 9];
+        for (row, line) in solved.iter().enumerate() {
+            for (col, &value) in line.iter().enumerate() {
+                if (row + col) % 2 == 1 {
+                    givens[row][col] = Some(value);
+                }
+            }
+        }
+        let puzzle = SudokuPuzzle { givens };
+        let mut board = solved;
+        board[4].swap(0, 2);
+        let config = SamplerConfig {
+            max_steps: 20_000,
+            schedule: from_default_start(Cooling::Reverse {
+                low: 0.25,
+                steps: 20_000,
+            }),
+            ..SamplerConfig::default()
+        };
+        for seed in 0..10 {
+            let start = SudokuState { board };
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (solution, stats) = solve_from(&puzzle, start, &config, &mut rng);
+            assert_eq!(stats.best_energy, 0, "seed {seed}");
+            assert!(respects_givens(&solution.board, &puzzle.givens));
+        }
+    }
+
+    #[test]
+    fn energy_breakdown_sums_to_the_sampler_energy() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let board = puzzle(21).random_initial_state(&mut rng).board;
+        for orientation in [Orientation::Rows, Orientation::Columns] {
+            for include_row_conflicts in [false, true] {
+                let config = SamplerConfig {
+                    orientation,
+                    include_row_conflicts,
+                    ..SamplerConfig::default()
+                };
+                let breakdown = energy_breakdown(&board, &config);
+                let oriented = SudokuState {
+                    board: orientation.orient(&board),
+                };
+                assert_eq!(breakdown.total(), config_energy(&oriented, &config));
+                let summed: usize = breakdown.parts().iter().map(|(_, conflicts)| conflicts).sum();
+                assert_eq!(summed, breakdown.total());
+                assert_eq!(breakdown.parts().len(), 2 + usize::from(include_row_conflicts));
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_batches_match_the_sequential_order() {
+        let puzzles: Vec<SudokuPuzzle> = (0..6).map(puzzle).collect();
+        let seeds: Vec<u64> = (10..16).collect();
+        let config = SamplerConfig {
+            max_steps: 5000,
+            ..SamplerConfig::default()
+        };
+        let summary = |jobs| -> Vec<([[u8; 9]; 9], usize, usize)> {
+            solve_batch(&puzzles, &seeds, &config, jobs)
+                .into_iter()
+                .map(|(solution, stats, _)| (solution.board, stats.steps, stats.best_energy))
+                .collect()
+        };
+        let sequential = summary(1);
+        assert_eq!(sequential.len(), 6);
+        assert_eq!(summary(4), sequential);
+        let (solution, stats) = solve(&puzzles[2], &config, &mut StdRng::seed_from_u64(12));
+        assert_eq!(sequential[2], (solution.board, stats.steps, stats.best_energy));
+    }
+
+    #[test]
+    fn board_distance_is_a_symmetric_cell_count() {
+        let solved = generate_full_solution(&mut StdRng::seed_from_u64(22));
+        let mut rng = StdRng::seed_from_u64(22);
+        let other = puzzle(22).random_initial_state(&mut rng).board;
+        assert_eq!(board_distance(&solved, &solved), 0);
+        assert_eq!(board_distance(&solved, &other), board_distance(&other, &solved));
+        let mut swapped = solved;
+        swapped[3].swap(1, 7);
+        swapped[8].swap(0, 4);
+        assert_eq!(board_distance(&solved, &swapped), 4);
+    }
+
+    #[test]
+    fn capped_initial_state_resamples_below_the_cap() {
+        let puzzle = puzzle(23);
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut energies: Vec<usize> =
+            (0..50).map(|_| puzzle.random_initial_state(&mut rng).energy()).collect();
+        energies.sort();
+        let cap = energies[5];
+        let config = SamplerConfig {
+            initial_energy_cap: Some(cap),
+            initial_resamples: 500,
+            ..SamplerConfig::default()
+        };
+        let (start, resamples) = puzzle.capped_initial_state(&config, &mut rng);
+        assert!(start.energy() <= cap);
+        assert!(resamples <= 500);
+
+        let impossible = SamplerConfig {
+            initial_energy_cap: Some(0),
+            initial_resamples: 7,
+            ..SamplerConfig::default()
+        };
+        let (start, resamples) = puzzle.capped_initial_state(&impossible, &mut rng);
+        assert_eq!(resamples, 7);
+        assert!(start.energy() > 0);
+        let uncapped = puzzle.capped_initial_state(&SamplerConfig::default(), &mut rng);
+        assert_eq!(uncapped.1, 0);
+    }
+
+    #[test]
+    fn column_orientation_solves_what_row_orientation_solves() {
+        let puzzle = puzzle(5);
+        let rows = SamplerConfig::default();
+        let (_, stats) = solve(&puzzle, &rows, &mut StdRng::seed_from_u64(5));
+        assert_eq!(stats.best_energy, 0);
+
+        let columns = SamplerConfig {
+            orientation: Orientation::Columns,
+            ..SamplerConfig::default()
+        };
+        let solved = (0..5).find_map(|seed| {
+            let (solution, stats) = solve(&puzzle, &columns, &mut StdRng::seed_from_u64(seed));
+            (stats.best_energy == 0).then_some(solution)
+        });
+        let solution = solved.expect("no seed solved the puzzle column by column");
+        assert!(respects_givens(&solution.board, &puzzle.givens));
+        assert_eq!(board_energy(&solution.board), 0);
+        assert_eq!(row_conflicts(&solution.board), 0);
+    }
+
+    #[test]
+    fn checking_separates_contradicted_givens_from_conflicts() {
+        let solved = generate_full_solution(&mut StdRng::seed_from_u64(25));
+        let mut givens = [[None; 9]; 9];
+        for (row, line) in solved.iter().enumerate() {
+            for (col, &value) in line.iter().enumerate() {
+                if (row + col) % 3 == 0 {
+                    givens[row][col] = Some(value);
+                }
+            }
+        }
+        let puzzle = SudokuPuzzle { givens };
+        assert!(check_solution(&puzzle, &solved).is_correct());
+
+        // Relabelling two digits keeps a valid Sudoku that contradicts the givens.
+        let swap_one_and_two = |value: u8| [0, 2, 1, 3, 4, 5, 6, 7, 8, 9][value as usize];
+        let relabelled = solved.map(|line| line.map(swap_one_and_two));
+        let result = check_solution(&puzzle, &relabelled);
+        assert!(result.conflicts.is_empty() && result.empty.is_empty());
+        let ones_and_twos = givens.iter().flatten().flatten().filter(|&&value| value <= 2);
+        assert_eq!(result.violated_givens.len(), ones_and_twos.count());
+        let (row, col, given, found) = result.violated_givens[0];
+        assert_eq!((given, found), (solved[row][col], relabelled[row][col]));
+
+        // Copying a neighbour into a free cell breaks the board but no given.
+        let mut broken = solved;
+        broken[0][1] = broken[0][2];
+        let result = check_solution(&puzzle, &broken);
+        assert!(result.violated_givens.is_empty());
+        assert!(result.conflicts.contains(&(0, 1)) && result.conflicts.contains(&(0, 2)));
+        broken[0][1] = 0;
+        assert_eq!(check_solution(&puzzle, &broken).empty, [(0, 1)]);
+    }
+
+    #[test]
+    fn escalation_stops_at_the_first_budget_that_solves() {
+        let easy = SudokuPuzzle::reproduce(2, 20);
+        let config = SamplerConfig::default();
+        let run = solve_escalating(&easy, &config, 5000, &mut StdRng::seed_from_u64(2));
+        assert_eq!((run.tries, run.budget, run.stats.best_energy), (1, 5000, 0));
+        assert_eq!(run.total_steps, run.stats.steps);
+
+        let capped = SamplerConfig {
+            max_steps: 4000,
+            ..SamplerConfig::default()
+        };
+        let hard = SudokuPuzzle::reproduce(1, 60);
+        let run = solve_escalating(&hard, &capped, 500, &mut StdRng::seed_from_u64(1));
+        assert!(run.tries <= 4 && run.budget == 500 << (run.tries - 1));
+        assert!(run.stats.best_energy == 0 || run.budget == 4000);
+    }
+
+    #[test]
+    fn completions_yield_both_fillings_of_a_swappable_pair() {
+        // Rows of the top band are shifts of `123456789` by 0, 3 and 6 places, so the 1s and
+        // 4s of the band can trade places and emptying those six cells leaves two answers.
+        let mut solved = [[0u8; 9]; 9];
+        for (row, line) in solved.iter_mut().enumerate() {
+            for (col, value) in line.iter_mut().enumerate() {
+                *value = ((row * 3 + row / 3 + col) % 9 + 1) as u8;
+            }
+        }
+        let mut givens = solved.map(|line| line.map(Some));
+        for (row, col) in [(0, 0), (0, 3), (1, 0), (1, 6), (2, 3), (2, 6)] {
+            assert!(matches!(solved[row][col], 1 | 4));
+            givens[row][col] = None;
+        }
+        let puzzle = SudokuPuzzle { givens };
+        assert_eq!(count_solutions(&givens, 10), 2);
+        let completions: Vec<SudokuState> = completions_iter(&puzzle).collect();
+        assert_eq!(completions.len(), 2);
+        assert_ne!(completions[0].board, completions[1].board);
+        for completion in &completions {
+            assert_eq!(board_energy(&completion.board), 0);
+            assert_eq!(row_conflicts(&completion.board), 0);
+            assert!(respects_givens(&completion.board, &givens));
+        }
+        assert!(completions.iter().any(|completion| completion.board == solved));
+        let mut iter = completions_iter(&puzzle);
+        assert!(iter.nth(2).is_none());
+    }
+
+    #[test]
+    fn score_all_keeps_the_input_order() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut boards: Vec<[[u8; 9]; 9]> = (0..4)
+            .map(|seed| puzzle(seed).random_initial_state(&mut rng).board)
+            .collect();
+        boards.push(generate_full_solution(&mut rng));
+        let expected: Vec<usize> = boards.iter().map(board_energy).collect();
+        assert_eq!(score_all(&boards), expected);
+        assert_eq!(expected[4], 0);
+        assert!(expected[..4].iter().any(|&energy| energy > 0));
+        assert!(score_all([]).is_empty());
+    }
+
+    #[test]
+    fn floored_metropolis_still_accepts_huge_uphill_moves() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut accepted = |floor| {
+            (0..100_000).filter(|_| metropolis_floored(1e6, 0.01, floor, &mut rng)).count()
+        };
+        assert_eq!(accepted(0.0), 0);
+        let floored = accepted(0.01);
+        assert!((800..1200).contains(&floored), "{floored}");
+        assert_eq!(accepted(1.0), 100_000);
+    }
+
+    #[test]
+    fn generated_puzzles_are_unique_and_graded_as_requested() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for difficulty in Difficulty::ALL {
+            let Some(generated) = generate_puzzle(difficulty, &mut rng) else {
+                panic!("no {difficulty} puzzle");
+            };
+            assert_eq!(count_solutions(&generated.givens, 2), 1);
+            assert_eq!(estimate_difficulty(&generated), Some(difficulty));
+        }
+    }
+
+    #[test]
+    fn replayed_moves_reproduce_the_final_board() {
+        let recorded = |max_steps, orientation| SamplerConfig {
+            max_steps,
+            orientation,
+            record_moves: true,
+            ..SamplerConfig::default()
+        };
+        for config in [
+            recorded(DEFAULT_MAX_STEPS, Orientation::Rows),
+            recorded(2000, Orientation::Rows),
+            recorded(2000, Orientation::Columns),
+        ] {
+            let (solution, stats) = solve(&puzzle(5), &config, &mut StdRng::seed_from_u64(5));
+            assert_eq!(stats.moves.len(), stats.steps);
+            let (replayed, best_energy) = replay_moves(&stats.initial_board, &stats.moves, &config);
+            assert_eq!(replayed.board, solution.board);
+            assert_eq!(best_energy, stats.best_energy);
+        }
+    }
+
+    #[test]
+    fn board_one_swap_from_valid_gets_that_swap_as_its_fix() {
+        let puzzle = generate_puzzle(Difficulty::Easy, &mut StdRng::seed_from_u64(8)).unwrap();
+        let solution = completions_iter(&puzzle).next().unwrap().board;
+        assert_eq!(suggest_fix(&puzzle, &solution), Some(Vec::new()));
+
+        let free = puzzle.row_free_positions();
+        let row = (0..9).find(|&row| free[row].len() >= 2).unwrap();
+        let (col_a, col_b) = (free[row][0], free[row][1]);
+        let mut board = solution;
+        board[row].swap(col_a, col_b);
+        let fixes = suggest_fix(&puzzle, &board).unwrap();
+        assert_eq!(fixes.len(), 2);
+        assert_eq!(fixes_as_swaps(&fixes), Some(vec![(row, col_a, col_b)]));
+
+        let mut clashing = puzzle.clone();
+        clashing.givens[row][col_a] = clashing.givens[row].iter().flatten().next().copied();
+        assert_eq!(suggest_fix(&clashing, &board), None);
+    }
+
+    #[test]
+    fn ensemble_mean_energy_matches_one_long_chain() {
+        let (puzzle, temperature, burn_in) = (puzzle(6), 1.5, 2_000);
+        let mean = |samples: &[usize]| samples.iter().sum::<usize>() as f64 / samples.len() as f64;
+        let seeds: Vec<u64> = (0..8).collect();
+        let chains = sample_ensemble(&puzzle, temperature, 20_000, &seeds, 4);
+        assert_eq!(chains, sample_ensemble(&puzzle, temperature, 20_000, &seeds, 1));
+        let pooled: Vec<usize> =
+            chains.iter().flat_map(|chain| chain[burn_in..].iter().copied()).collect();
+
+        let mut rng = StdRng::seed_from_u64(100);
+        let long = sample_fixed_temperature(&puzzle, temperature, 160_000, &mut rng);
+        let (ensemble, sequential) = (mean(&pooled), mean(&long[burn_in..]));
+        assert!((ensemble - sequential).abs() < 0.05 * sequential, "{ensemble} vs {sequential}");
+    }
+
+    #[test]
+    fn perturbed_solution_energy_grows_with_the_noise() {
+        let puzzle = puzzle(5);
+        let solution = completions_iter(&puzzle).next().unwrap().board;
+        let mean_energy = |swaps| {
+            let total: usize = (0..20)
+                .map(|seed| {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let state = puzzle.perturbed(&solution, swaps, Orientation::Rows, &mut rng);
+                    assert!(respects_givens(&state.board, &puzzle.givens));
+                    assert_eq!(row_conflicts(&state.board), 0);
+                    state.energy()
+                })
+                .sum();
+            total as f64 / 20.0
+        };
+        let means: Vec<f64> = [0, 1, 2, 4, 8, 16].into_iter().map(mean_energy).collect();
+        assert_eq!(means[0], 0.0);
+        assert!(means[1] >= 2.0, "{means:?}");
+        assert!(means.windows(2).all(|pair| pair[0] < pair[1]), "{means:?}");
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let columns = puzzle.perturbed(&solution, 3, Orientation::Columns, &mut rng);
+        assert!(respects_givens(&columns.board, &puzzle.givens));
+        assert_eq!(column_conflicts(&columns.board), 0);
+        assert!(row_conflicts(&columns.board) > 0);
+    }
+
+    #[test]
+    fn reported_acceptance_ratio_covers_the_last_window() {
+        let config = SamplerConfig {
+            max_steps: 3000,
+            record_moves: true,
+            ..SamplerConfig::default()
+        };
+        let (_, stats) = solve(&puzzle(5), &config, &mut StdRng::seed_from_u64(5));
+        let window = &stats.moves[stats.moves.len() - ACCEPTANCE_WINDOW..];
+        let accepted = window.iter().filter(|record| record.accepted).count();
+        let expected = accepted as f64 / ACCEPTANCE_WINDOW as f64;
+        assert_eq!(stats.acceptance_ratio, Some(expected));
+
+        let short = SamplerConfig {
+            max_steps: ACCEPTANCE_WINDOW - 1,
+            ..SamplerConfig::default()
+        };
+        let (_, stats) = solve(&puzzle(5), &short, &mut StdRng::seed_from_u64(5));
+        assert_eq!(stats.acceptance_ratio, None);
+
+        let adaptive = SamplerConfig {
+            max_steps: 20_000,
+            schedule: from_default_start(Cooling::Adaptive {
+                target: 0.4,
+                band: 0.05,
+                rate: DEFAULT_COOLING_RATE,
+            }),
+            ..SamplerConfig::default()
+        };
+        let (_, stats) = solve(&puzzle(7), &adaptive, &mut StdRng::seed_from_u64(7));
+        assert!(stats.steps <= adaptive.max_steps);
+        assert!(stats.temperature >= adaptive.temperature_floor);
+    }
+
+    #[test]
+    fn replay_with_zero_weights_keeps_the_solution_as_best() {
+        let config = SamplerConfig {
+            cell_weights: Some([[0.0; 9]; 9]),
+            record_moves: true,
+            ..SamplerConfig::default()
+        };
+        let solution = generate_full_solution(&mut StdRng::seed_from_u64(5));
+        let mut givens = solution.map(|row| row.map(Some));
+        (givens[0][0], givens[0][1]) = (None, None);
+        let mut start = solution;
+        start[0].swap(0, 1);
+        let near = (SudokuPuzzle { givens }, SudokuState { board: start });
+        let (best, stats) = solve_from(&near.0, near.1, &config, &mut StdRng::seed_from_u64(6));
+        assert_eq!((best.board, stats.best_energy), (solution, 0));
+        // Every board scores 0, so only the solution rule moves the best away from the start.
+        let (replayed, best_energy) = replay_moves(&stats.initial_board, &stats.moves, &config);
+        assert_eq!((replayed.board, best_energy), (solution, 0));
+
+        let short = SamplerConfig {
+            max_steps: 3000,
+            ..config
+        };
+        let (best, stats) = solve(&puzzle(5), &short, &mut StdRng::seed_from_u64(5));
+        let (replayed, best_energy) = replay_moves(&stats.initial_board, &stats.moves, &short);
+        assert_eq!((replayed.board, best_energy), (best.board, stats.best_energy));
+    }
+
+    #[test]
+    fn acceptance_window_sets_how_many_proposals_the_ratio_covers() {
+        let ratio = |window| {
+            let config = SamplerConfig {
+                max_steps: 3000,
+                record_moves: true,
+                acceptance_window: window,
+                ..SamplerConfig::default()
+            };
+            solve(&puzzle(5), &config, &mut StdRng::seed_from_u64(5)).1
+        };
+        for window in [1, 50] {
+            let stats = ratio(window);
+            let last = &stats.moves[stats.moves.len() - window..];
+            let accepted = last.iter().filter(|record| record.accepted).count();
+            assert_eq!(stats.acceptance_ratio, Some(accepted as f64 / window as f64));
+        }
+        assert_eq!(ratio(0).acceptance_ratio, ratio(1).acceptance_ratio);
+    }
+
+    #[test]
+    fn fixed_temperature_chain_and_perturbation_step_like_the_sampler() {
+        // Near zero temperature the chain takes no uphill swap, so its energy never rises.
+        let mut rng = StdRng::seed_from_u64(9);
+        let samples = sample_fixed_temperature(&puzzle(9), 1e-9, 2000, &mut rng);
+        assert_eq!(samples.len(), 2000);
+        assert!(samples.windows(2).all(|pair| pair[1] <= pair[0]));
+        // One perturbation swap always moves exactly two cells, however many rows are full.
+        let puzzle = SudokuPuzzle::reproduce(9, 70);
+        let solution = completions_iter(&puzzle).next().unwrap().board;
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let state = puzzle.perturbed(&solution, 1, Orientation::Rows, &mut rng);
+            assert_eq!(board_distance(&state.board, &solution), 2, "seed {seed}");
+        }
+    }
 }
@@ -0,0 +1,182 @@
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Deserialize;
+use std::{cmp::Reverse, error::Error};
+
+use crate::{
+    acceptance::AcceptanceRule,
+    schedule::ScheduleSpec,
+    sudoku::{self, Orientation, SamplerConfig, SudokuPuzzle},
+};
+
+/// One entry of a tournament file. Fields left out keep the value of the base config.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContenderSpec {
+    name: String,
+    max_steps: Option<usize>,
+    start_temp: Option<f64>,
+    cooling_rate: Option<f64>,
+    /// Parsed like `--schedule`, e.g. `"adaptive:0.3:0.05"`.
+    schedule: Option<String>,
+    /// Parsed like `--acceptance`, e.g. `"threshold:2:0.9995"`.
+    acceptance: Option<String>,
+    /// `"rows"` or `"columns"`, like `--orientation`.
+    orientation: Option<String>,
+    temp_floor: Option<f64>,
+    lateral_accept_prob: Option<f64>,
+    max_uphill: Option<usize>,
+}
+
+/// How one contender fared over every seed of a tournament.
+#[derive(Clone, Debug)]
+pub struct Standing {
+    pub name: String,
+    pub solved: usize,
+    pub runs: usize,
+    /// Swaps summed over all runs, unsolved ones included.
+    pub total_steps: usize,
+}
+
+impl Standing {
+    pub fn solve_rate(&self) -> f64 {
+        self.solved as f64 / self.runs.max(1) as f64
+    }
+
+    pub fn mean_steps(&self) -> f64 {
+        self.total_steps as f64 / self.runs.max(1) as f64
+    }
+}
+
+/// Parses a JSON array of named parameter sets, each applied on top of `base`. A contender's
+/// schedule is rebuilt from its `schedule`, `start_temp`, and `cooling_rate`, falling back to
+/// geometric cooling, the start of `base.schedule`, and `cooling_rate` (clamped as for
+/// `--cooling-rate`).
+pub fn parse_contenders(
+    text: &str,
+    base: &SamplerConfig,
+    cooling_rate: f64,
+) -> Result<Vec<(String, SamplerConfig)>, Box<dyn Error>> {
+    let specs: Vec<ContenderSpec> = serde_json::from_str(text)?;
+    if specs.is_empty() {
+        return Err("tournament file lists no contenders".into());
+    }
+    specs
+        .into_iter()
+        .map(|spec| {
+            let mut config = base.clone();
+            config.max_steps = spec.max_steps.unwrap_or(config.max_steps);
+            config.temperature_floor = spec.temp_floor.unwrap_or(config.temperature_floor);
+            config.lateral_accept_prob =
+                spec.lateral_accept_prob.unwrap_or(config.lateral_accept_prob);
+            config.max_consecutive_uphill = spec.max_uphill.or(config.max_consecutive_uphill);
+            let schedule = match &spec.schedule {
+                Some(schedule) => schedule
+                    .parse::<ScheduleSpec>()
+                    .map_err(|err| format!("contender {:?}: {err}", spec.name))?,
+                None => ScheduleSpec::default(),
+            };
+            config.schedule = schedule.schedule(
+                spec.start_temp.unwrap_or(base.schedule.start_temp),
+                sudoku::clamp_cooling_rate(spec.cooling_rate.unwrap_or(cooling_rate)),
+                config.max_steps,
+            );
+            if let Some(acceptance) = &spec.acceptance {
+                config.acceptance = acceptance
+                    .parse::<AcceptanceRule>()
+                    .map_err(|err| format!("contender {:?}: {err}", spec.name))?;
+            }
+            if let Some(orientation) = &spec.orientation {
+                config.orientation = orientation
+                    .parse::<Orientation>()
+                    .map_err(|err| format!("contender {:?}: {err}", spec.name))?;
+            }
+            Ok((spec.name, config))
+        })
+        .collect()
+}
+
+/// Solves one puzzle per seed with every contender and returns the standings, best first:
+/// most puzzles solved, then fewest mean swaps, then file order. Each seed generates its
+/// puzzle exactly as `sudoku --seed <N>` would, and every contender starts its solve from
+/// the same RNG state, so they face identical puzzles and initial boards.
+pub fn run_tournament(
+    contenders: &[(String, SamplerConfig)],
+    seeds: &[u64],
+    holes: usize,
+) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = contenders
+        .iter()
+        .map(|(name, _)| Standing {
+            name: name.clone(),
+            solved: 0,
+            runs: seeds.len(),
+            total_steps: 0,
+        })
+        .collect();
+    for &seed in seeds {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let puzzle = SudokuPuzzle::with_random_holes(holes, &mut rng);
+        for ((_, config), standing) in contenders.iter().zip(&mut standings) {
+            let (_, stats) = sudoku::solve(&puzzle, config, &mut rng.clone());
+            standing.solved += usize::from(stats.best_energy == 0);
+            standing.total_steps += stats.steps;
+        }
+    }
+    standings.sort_by_key(|standing| (Reverse(standing.solved), standing.total_steps));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{Cooling, CoolingSchedule};
+
+    #[test]
+    fn the_better_contender_wins_deterministically() {
+        let text = r#"[{"name": "starved", "max_steps": 100}, {"name": "full"}]"#;
+        let rate = sudoku::DEFAULT_COOLING_RATE;
+        let contenders = parse_contenders(text, &SamplerConfig::default(), rate).unwrap();
+        assert_eq!(contenders[0].1.max_steps, 100);
+        let seeds = [3, 4, 5];
+        let first = run_tournament(&contenders, &seeds, 48);
+        let names: Vec<&str> = first.iter().map(|standing| standing.name.as_str()).collect();
+        assert_eq!(names, ["full", "starved"]);
+        assert_eq!((first[0].solved, first[1].solved), (3, 0));
+        let second = run_tournament(&contenders, &seeds, 48);
+        let totals = |standings: &[Standing]| -> Vec<usize> {
+            standings.iter().map(|standing| standing.total_steps).collect()
+        };
+        assert_eq!(totals(&first), totals(&second));
+    }
+
+    #[test]
+    fn malformed_contenders_are_rejected() {
+        let (base, rate) = (SamplerConfig::default(), sudoku::DEFAULT_COOLING_RATE);
+        assert!(parse_contenders("[]", &base, rate).is_err());
+        assert!(parse_contenders(r#"[{"name": "a", "speed": 2}]"#, &base, rate).is_err());
+        let warp = r#"[{"name": "a", "schedule": "warp"}]"#;
+        let err = parse_contenders(warp, &base, rate).unwrap_err();
+        assert!(err.to_string().starts_with("contender \"a\": "), "{err}");
+    }
+
+    #[test]
+    fn contender_schedules_are_rebuilt_from_their_own_parameters() {
+        let text = r#"[
+            {"name": "plain"},
+            {"name": "hot", "start_temp": 4.0, "cooling_rate": 0.5},
+            {"name": "linear", "schedule": "linear", "max_steps": 100}
+        ]"#;
+        let base = SamplerConfig::default();
+        let contenders = parse_contenders(text, &base, sudoku::DEFAULT_COOLING_RATE).unwrap();
+        assert_eq!(contenders[0].1.schedule, base.schedule);
+        // The contender's rate is clamped just like `--cooling-rate`.
+        assert_eq!(contenders[1].1.schedule, CoolingSchedule::geometric(4.0, 0.8));
+        let linear = CoolingSchedule {
+            start_temp: sudoku::DEFAULT_START_TEMP,
+            cooling: Cooling::Linear {
+                step: sudoku::DEFAULT_START_TEMP / 100.0,
+            },
+        };
+        assert_eq!(contenders[2].1.schedule, linear);
+    }
+}
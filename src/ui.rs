@@ -1,64 +1,418 @@
-use colored::Colorize;
+use crate::analysis::BatchTally;
+use colored::{ColoredString, Colorize};
 use ratatui::{
-    backend::CrosstermBackend,
-    layout::Constraint,
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::Span,
-    widgets::{Block, Borders, Cell, Row, Table},
-    Terminal,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table},
+    Terminal, TerminalOptions, Viewport,
 };
-use std::{error::Error, io::stdout};
+use std::{
+    error::Error,
+    io::{self, IsTerminal, Stdout, Write, stdout},
+    str::FromStr,
+    time::Duration,
+};
+
+/// Spacing and separators of the ASCII Sudoku grid. The default is the classic look,
+/// `| 5 3 4 | 6 7 8 | 9 1 2 |` between `+-------+-------+-------+` rules.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RenderStyle {
+    /// Spaces between neighbouring digits, and between a digit and a box separator. Without
+    /// a box separator, boxes are set apart by twice this many spaces.
+    pub cell_pad: usize,
+    /// Character between boxes and at both edges of each row; `None` leaves them open.
+    pub box_sep: Option<char>,
+    /// Draw a horizontal rule above, between, and below the bands of boxes.
+    pub line_sep: bool,
+    /// Label the columns 1-9 in a header line and the rows 1-9 down the left edge.
+    pub coords: bool,
+    /// How given cells stand out from the solver's digits.
+    pub givens: GivenHighlight,
+}
+
+impl RenderStyle {
+    pub const CLASSIC: RenderStyle = RenderStyle {
+        cell_pad: 1,
+        box_sep: Some('|'),
+        line_sep: true,
+        coords: false,
+        givens: GivenHighlight::Color,
+    };
+    /// Digits only, e.g. `534678912`, for logs.
+    pub const TIGHT: RenderStyle = RenderStyle {
+        cell_pad: 0,
+        box_sep: None,
+        line_sep: false,
+        coords: false,
+        givens: GivenHighlight::Color,
+    };
+    /// `|  5  3  4  |  6  7  8  | …`, for docs and slides.
+    pub const WIDE: RenderStyle = RenderStyle {
+        cell_pad: 2,
+        box_sep: Some('|'),
+        line_sep: true,
+        coords: false,
+        givens: GivenHighlight::Color,
+    };
+
+    /// The horizontal rule, as wide as a row.
+    fn rule(&self) -> String {
+        let box_width = self.cell_pad + 3 * (1 + self.cell_pad);
+        match self.box_sep {
+            Some(_) => format!("+{}", format!("{}+", "-".repeat(box_width)).repeat(3)),
+            None => "-".repeat(9 + 10 * self.cell_pad),
+        }
+    }
+}
+
+/// How the boards mark the givens: a cyan digit, or a dark digit on a light gray fill like
+/// the clues in a printed puzzle book. Both need a terminal that shows colors.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GivenHighlight {
+    #[default]
+    Color,
+    Background,
+}
+
+impl GivenHighlight {
+    /// A given digit as the ASCII boards print it.
+    fn paint(self, token: String) -> ColoredString {
+        match self {
+            GivenHighlight::Color => token.cyan(),
+            GivenHighlight::Background => token.black().on_white(),
+        }
+    }
+
+    /// A given digit's style in the ratatui boards.
+    fn tui_style(self) -> Style {
+        match self {
+            GivenHighlight::Color => Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            GivenHighlight::Background => Style::default().fg(Color::Black).bg(Color::Gray),
+        }
+    }
+
+    fn legend(self) -> &'static str {
+        match self {
+            GivenHighlight::Color => "givens in cyan",
+            GivenHighlight::Background => "givens on gray",
+        }
+    }
+}
+
+impl FromStr for GivenHighlight {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "color" => Ok(GivenHighlight::Color),
+            "bg" => Ok(GivenHighlight::Background),
+            _ => Err(format!("unknown given highlight {text:?} (bg, color)")),
+        }
+    }
+}
+
+impl Default for RenderStyle {
+    fn default() -> Self {
+        RenderStyle::CLASSIC
+    }
+}
+
+impl FromStr for RenderStyle {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "classic" => Ok(RenderStyle::CLASSIC),
+            "tight" => Ok(RenderStyle::TIGHT),
+            "wide" => Ok(RenderStyle::WIDE),
+            _ => Err(format!("unknown grid style {text:?} (classic, tight, wide)")),
+        }
+    }
+}
 
 pub fn print_given_grid(givens: &[[Option<u8>; 9]; 9]) {
-    println!("{}", "Sudoku puzzle (givens in cyan)".bright_blue());
+    print_given_grid_styled(givens, &RenderStyle::default());
+}
+
+pub fn print_given_grid_styled(givens: &[[Option<u8>; 9]; 9], style: &RenderStyle) {
+    write_given_grid_styled(&mut stdout().lock(), givens, style)
+        .expect("failed to write to stdout");
+}
+
+pub fn write_given_grid(out: &mut impl Write, givens: &[[Option<u8>; 9]; 9]) -> io::Result<()> {
+    write_given_grid_styled(out, givens, &RenderStyle::default())
+}
+
+pub fn write_given_grid_styled(
+    out: &mut impl Write,
+    givens: &[[Option<u8>; 9]; 9],
+    style: &RenderStyle,
+) -> io::Result<()> {
+    let title = format!("Sudoku puzzle ({})", style.givens.legend());
+    writeln!(out, "{}", title.bright_blue())?;
+    write_grid(out, style, |row, col| match givens[row][col] {
+        Some(value) => (style.givens.paint(format!("{value}")), false),
+        None => (".".normal(), false),
+    })
+}
+
+pub fn print_sudoku_ascii(
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+    box_shading: bool,
+) {
+    write_sudoku_ascii(&mut stdout().lock(), board, givens, mask, box_shading)
+        .expect("failed to write to stdout");
+}
+
+pub fn write_sudoku_ascii(
+    out: &mut impl Write,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+    box_shading: bool,
+) -> io::Result<()> {
+    write_sudoku_titled(out, "Final Sudoku state", board, givens, mask, box_shading)
+}
+
+/// `print_sudoku_ascii` under a heading other than "Final Sudoku state".
+pub fn print_sudoku_titled(
+    title: &str,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+    box_shading: bool,
+) {
+    print_sudoku_styled(title, board, givens, mask, box_shading, &RenderStyle::default());
+}
+
+pub fn write_sudoku_titled(
+    out: &mut impl Write,
+    title: &str,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+    box_shading: bool,
+) -> io::Result<()> {
+    write_sudoku_styled(out, title, board, givens, mask, box_shading, &RenderStyle::default())
+}
+
+/// `print_sudoku_titled` with the grid laid out by `style`.
+pub fn print_sudoku_styled(
+    title: &str,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+    box_shading: bool,
+    style: &RenderStyle,
+) {
+    write_sudoku_styled(&mut stdout().lock(), title, board, givens, mask, box_shading, style)
+        .expect("failed to write to stdout");
+}
+
+pub fn write_sudoku_styled(
+    out: &mut impl Write,
+    title: &str,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+    box_shading: bool,
+    style: &RenderStyle,
+) -> io::Result<()> {
+    writeln!(out, "{}", title.bright_blue())?;
+    write_grid(out, style, |row, col| {
+        let token = format!("{}", board[row][col]);
+        let styled = if mask[row][col] {
+            token.red().bold()
+        } else if givens[row][col].is_some() {
+            style.givens.paint(token)
+        } else {
+            token.yellow()
+        };
+        (styled, box_shading && !mask[row][col] && is_shaded_box(row, col))
+    })
+}
+
+/// A fixed board with the `changed` cells in bold green, for the second pass after a
+/// best-effort solve (`sudoku::suggest_fix`).
+pub fn print_sudoku_fix(
+    title: &str,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    changed: &[[bool; 9]; 9],
+    box_shading: bool,
+    style: &RenderStyle,
+) {
+    write_sudoku_fix(&mut stdout().lock(), title, board, givens, changed, box_shading, style)
+        .expect("failed to write to stdout");
+}
+
+pub fn write_sudoku_fix(
+    out: &mut impl Write,
+    title: &str,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    changed: &[[bool; 9]; 9],
+    box_shading: bool,
+    style: &RenderStyle,
+) -> io::Result<()> {
+    writeln!(out, "{}", title.bright_blue())?;
+    write_grid(out, style, |row, col| {
+        let token = format!("{}", board[row][col]);
+        let styled = if changed[row][col] {
+            token.bright_green().bold()
+        } else if givens[row][col].is_some() {
+            style.givens.paint(token)
+        } else {
+            token.yellow()
+        };
+        (styled, box_shading && !changed[row][col] && is_shaded_box(row, col))
+    })
+}
+
+/// Lays out the 81 cells as `style` asks. `cell(row, col)` gives each coloured digit and
+/// whether to shade it; shading also covers the padding that follows the digit.
+fn write_grid(
+    out: &mut impl Write,
+    style: &RenderStyle,
+    cell: impl Fn(usize, usize) -> (ColoredString, bool),
+) -> io::Result<()> {
+    let pad = " ".repeat(style.cell_pad);
+    let rule = style.rule();
+    let margin = if style.coords { "  " } else { "" };
+    if style.coords {
+        // Laid out like a row, with blanks for the box separators, so each number sits
+        // above its column.
+        let mut header = Vec::new();
+        let blank_sep = style.box_sep.map(|_| ' ');
+        write_row(&mut header, &pad, blank_sep, |col| ((col + 1).to_string().normal(), false))?;
+        writeln!(out, "{margin}{}", String::from_utf8_lossy(&header).trim_end())?;
+    }
     for row in 0..9 {
-        if row % 3 == 0 {
-            println!("+-------+-------+-------+");
+        if style.line_sep && row % 3 == 0 {
+            writeln!(out, "{margin}{rule}")?;
         }
-        for col in 0..9 {
-            if col % 3 == 0 {
-                print!("| ");
-            }
-            match givens[row][col] {
-                Some(value) => print!("{} ", format!("{value}").cyan()),
-                None => print!(". "),
+        if style.coords {
+            write!(out, "{} ", row + 1)?;
+        }
+        write_row(out, &pad, style.box_sep, |col| cell(row, col))?;
+    }
+    if style.line_sep {
+        writeln!(out, "{margin}{rule}")?;
+    }
+    Ok(())
+}
+
+/// One line of `write_grid`, with `box_sep` between boxes and at both edges.
+fn write_row(
+    out: &mut impl Write,
+    pad: &str,
+    box_sep: Option<char>,
+    cell: impl Fn(usize) -> (ColoredString, bool),
+) -> io::Result<()> {
+    for col in 0..9 {
+        if col % 3 == 0 {
+            match box_sep {
+                Some(sep) => write!(out, "{sep}{pad}")?,
+                None if col > 0 => write!(out, "{pad}")?,
+                None => {}
             }
         }
-        println!("|");
+        let (styled, shaded) = cell(col);
+        let gap = if col == 8 && box_sep.is_none() { "" } else { pad };
+        if shaded {
+            write!(out, "{}", format!("{styled}{gap}").on_bright_black())?;
+        } else {
+            write!(out, "{styled}{gap}")?;
+        }
+    }
+    match box_sep {
+        Some(sep) => writeln!(out, "{sep}"),
+        None => writeln!(out),
     }
-    println!("+-------+-------+-------+");
 }
 
-pub fn print_sudoku_ascii(
+pub fn print_sudoku_compact(
+    title: &str,
     board: &[[u8; 9]; 9],
     givens: &[[Option<u8>; 9]; 9],
     mask: &[[bool; 9]; 9],
 ) {
-    println!("{}", "Final Sudoku state".bright_blue());
+    write_sudoku_compact(&mut stdout().lock(), title, board, givens, mask)
+        .expect("failed to write to stdout");
+}
+
+/// The board on one line after `title`: `|` between boxes within a row, a space between
+/// rows, and ` / ` between bands of boxes, e.g. `534|678|912 672|195|348 … / …`. Cells are
+/// coloured as in `write_sudoku_ascii`.
+pub fn write_sudoku_compact(
+    out: &mut impl Write,
+    title: &str,
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+) -> io::Result<()> {
+    write!(out, "{} ", format!("{title}:").bright_blue())?;
     for row in 0..9 {
-        if row % 3 == 0 {
-            println!("+-------+-------+-------+");
+        if row > 0 {
+            write!(out, "{}", if row % 3 == 0 { " / " } else { " " })?;
         }
         for col in 0..9 {
-            if col % 3 == 0 {
-                print!("| ");
+            if col > 0 && col % 3 == 0 {
+                write!(out, "|")?;
             }
             let token = format!("{}", board[row][col]);
-            let styled = if mask[row][col] {
-                token.red().bold()
+            if mask[row][col] {
+                write!(out, "{}", token.red().bold())?;
             } else if givens[row][col].is_some() {
-                token.cyan()
+                write!(out, "{}", token.cyan())?;
             } else {
-                token.yellow()
-            };
-            print!("{} ", styled);
+                write!(out, "{}", token.yellow())?;
+            }
+        }
+    }
+    writeln!(out)
+}
+
+pub fn print_conflict_overlay(mask: &[[bool; 9]; 9]) {
+    write_conflict_overlay(&mut stdout().lock(), mask).expect("failed to write to stdout");
+}
+
+/// The conflict mask alone, `X` for a conflicting cell and `.` otherwise, laid out on the
+/// same grid as the board.
+pub fn write_conflict_overlay(out: &mut impl Write, mask: &[[bool; 9]; 9]) -> io::Result<()> {
+    writeln!(out, "{}", "Conflict overlay".bright_blue())?;
+    for row in 0..9 {
+        if row % 3 == 0 {
+            writeln!(out, "+-------+-------+-------+")?;
+        }
+        for col in 0..9 {
+            if col % 3 == 0 {
+                write!(out, "| ")?;
+            }
+            if mask[row][col] {
+                write!(out, "{} ", "X".red().bold())?;
+            } else {
+                write!(out, ". ")?;
+            }
         }
-        println!("|");
+        writeln!(out, "|")?;
     }
-    println!("+-------+-------+-------+");
+    writeln!(out, "+-------+-------+-------+")
 }
 
 pub fn print_queens_ascii(state: &[u8; 8], mask: [bool; 8]) {
+    write_queens_ascii(&mut stdout().lock(), state, mask).expect("failed to write to stdout");
+}
+
+pub fn write_queens_ascii(
+    out: &mut impl Write,
+    state: &[u8; 8],
+    mask: [bool; 8],
+) -> io::Result<()> {
     for (row, &queen_col) in state.iter().enumerate() {
         for col in 0..8 {
             if col == queen_col as usize {
@@ -67,22 +421,65 @@ pub fn print_queens_ascii(state: &[u8; 8], mask: [bool; 8]) {
                 } else {
                     "Q".green().bold()
                 };
-                print!("{} ", glyph);
+                write!(out, "{} ", glyph)?;
             } else {
-                print!(".");
-                print!(" ");
+                write!(out, ".")?;
+                write!(out, " ")?;
             }
         }
-        println!();
+        writeln!(out)?;
     }
-    println!();
+    writeln!(out)
+}
+
+/// Boxes alternate like a checkerboard; the corner and center boxes stay unshaded.
+pub fn is_shaded_box(row: usize, col: usize) -> bool {
+    (row / 3 + col / 3) % 2 == 1
+}
+
+pub fn print_queens_compact(state: &[u8; 8], mask: [bool; 8]) {
+    write_queens_compact(&mut stdout().lock(), state, mask).expect("failed to write to stdout");
+}
+
+/// The placement as its column vector on one line, e.g. `04752613`: the column of the queen
+/// in each row from the top, counting from 0. Conflicting queens are red.
+pub fn write_queens_compact(
+    out: &mut impl Write,
+    state: &[u8; 8],
+    mask: [bool; 8],
+) -> io::Result<()> {
+    for (row, &queen_col) in state.iter().enumerate() {
+        let token = queen_col.to_string();
+        if mask[row] {
+            write!(out, "{}", token.red().bold())?;
+        } else {
+            write!(out, "{}", token.green().bold())?;
+        }
+    }
+    writeln!(out)
 }
 
 pub fn render_sudoku_tui(
     board: &[[u8; 9]; 9],
     givens: &[[Option<u8>; 9]; 9],
     mask: &[[bool; 9]; 9],
+    box_shading: bool,
+    highlight: GivenHighlight,
+    energy_history: &[usize],
 ) -> Result<(), Box<dyn Error>> {
+    let table = sudoku_table_widget(board, givens, mask, box_shading, highlight);
+    draw_table(table, energy_history)
+}
+
+/// The Sudoku board as a 9×9 table widget, for rendering into any `Rect` of a caller's
+/// frame; `render_sudoku_tui` draws it full-screen.
+pub fn sudoku_table_widget(
+    board: &[[u8; 9]; 9],
+    givens: &[[Option<u8>; 9]; 9],
+    mask: &[[bool; 9]; 9],
+    box_shading: bool,
+    highlight: GivenHighlight,
+) -> Table<'static> {
     let cells: Vec<Vec<Cell>> = board
         .iter()
         .enumerate()
@@ -93,19 +490,29 @@ pub fn render_sudoku_tui(
                     let style = if mask[row][col] {
                         Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
                     } else if givens[row][col].is_some() {
-                        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                        highlight.tui_style()
                     } else {
                         Style::default().fg(Color::Yellow)
                     };
-                    Cell::from(Span::styled(format!("{value}"), style))
+                    let cell = Cell::from(Span::styled(format!("{value}"), style));
+                    if box_shading && !mask[row][col] && is_shaded_box(row, col) {
+                        cell.style(Style::default().bg(Color::DarkGray))
+                    } else {
+                        cell
+                    }
                 })
                 .collect()
         })
         .collect();
-    draw_cells_table(cells, "Sudoku thermodynamic grid", 9)
+    cells_table(cells, "Sudoku thermodynamic grid", 9)
 }
 
 pub fn render_queens_tui(solution: &[u8; 8], mask: [bool; 8]) -> Result<(), Box<dyn Error>> {
+    draw_table(queens_table_widget(solution, mask), &[])
+}
+
+/// The 8-Queens board as an 8×8 table widget, the counterpart of `sudoku_table_widget`.
+pub fn queens_table_widget(solution: &[u8; 8], mask: [bool; 8]) -> Table<'static> {
     let cells: Vec<Vec<Cell>> = solution
         .iter()
         .enumerate()
@@ -126,19 +533,440 @@ pub fn render_queens_tui(solution: &[u8; 8], mask: [bool; 8]) -> Result<(), Box<
                 .collect()
         })
         .collect();
-    draw_cells_table(cells, "8-Queens placement", 8)
+    cells_table(cells, "8-Queens placement", 8)
 }
 
-fn draw_cells_table(cells: Vec<Vec<Cell>>, title: &str, columns: usize) -> Result<(), Box<dyn Error>> {
+fn cells_table(cells: Vec<Vec<Cell<'static>>>, title: &'static str, columns: usize) -> Table<'static> {
+    let rows = cells.into_iter().map(Row::new).collect::<Vec<Row>>();
+    let widths = vec![Constraint::Length(3); columns];
+    Table::new(rows, widths).block(Block::default().title(title).borders(Borders::ALL))
+}
+
+/// Terminal cells one bordered `sudoku_table_widget` needs: nine 3-wide columns with the
+/// table's 1-wide gaps, plus the border.
+pub const BOARD_WIDTH: u16 = 9 * 3 + 8 + 2;
+pub const BOARD_HEIGHT: u16 = 9 + 2;
+
+/// A board for `render_sudoku_gallery`, drawn under its own title.
+pub struct LabeledBoard {
+    pub label: String,
+    pub board: [[u8; 9]; 9],
+    pub givens: [[Option<u8>; 9]; 9],
+    pub mask: [[bool; 9]; 9],
+}
+
+/// One `BOARD_WIDTH` × `BOARD_HEIGHT` region per board, filling `area` row by row from the
+/// top left, for as many of `boards` as fit.
+pub fn board_grid_layout(area: Rect, boards: usize) -> Vec<Rect> {
+    let columns = (area.width / BOARD_WIDTH) as usize;
+    let rows = (area.height / BOARD_HEIGHT) as usize;
+    (0..boards.min(columns * rows))
+        .map(|index| {
+            let (row, col) = ((index / columns) as u16, (index % columns) as u16);
+            Rect::new(
+                area.x + col * BOARD_WIDTH,
+                area.y + row * BOARD_HEIGHT,
+                BOARD_WIDTH,
+                BOARD_HEIGHT,
+            )
+        })
+        .collect()
+}
+
+/// Draws the boards side by side, `per_frame` to a page at most (`None`: as many as the
+/// terminal fits). Pages are drawn inline one below the other, so all of them stay in the
+/// scrollback.
+pub fn render_sudoku_gallery(
+    boards: &[LabeledBoard],
+    per_frame: Option<usize>,
+    box_shading: bool,
+    highlight: GivenHighlight,
+) -> Result<(), Box<dyn Error>> {
+    let stdout = stdout();
+    if !stdout.is_terminal() {
+        return Err("standard output is not a terminal".into());
+    }
+    let size = CrosstermBackend::new(stdout).size()?;
+    let columns = (size.width / BOARD_WIDTH).max(1) as usize;
+    let fits = columns * (size.height / BOARD_HEIGHT).max(1) as usize;
+    let page_size = per_frame.unwrap_or(fits).clamp(1, fits);
+    for page in boards.chunks(page_size) {
+        let height = page.len().div_ceil(columns) as u16 * BOARD_HEIGHT;
+        let options = TerminalOptions {
+            viewport: Viewport::Inline(height),
+        };
+        let mut terminal = Terminal::with_options(CrosstermBackend::new(io::stdout()), options)?;
+        let drawn = terminal
+            .draw(|frame| {
+                let areas = board_grid_layout(frame.area(), page.len());
+                for (board, area) in page.iter().zip(areas) {
+                    let block = Block::default().title(board.label.clone()).borders(Borders::ALL);
+                    let (cells, givens, mask) = (&board.board, &board.givens, &board.mask);
+                    let table = sudoku_table_widget(cells, givens, mask, box_shading, highlight)
+                        .block(block);
+                    frame.render_widget(table, area);
+                }
+            })
+            .map(|_| ());
+        terminal.show_cursor()?;
+        drawn?;
+        writeln!(io::stdout())?;
+    }
+    Ok(())
+}
+
+/// Buckets `energies` into at most `width` columns, keeping each bucket's peak so short
+/// spikes stay visible once a long history is squeezed into the terminal.
+pub fn sparkline_data(energies: &[usize], width: usize) -> Vec<u64> {
+    if energies.is_empty() || width == 0 {
+        return Vec::new();
+    }
+    let bucket = energies.len().div_ceil(width);
+    energies
+        .chunks(bucket)
+        .map(|chunk| chunk.iter().copied().max().unwrap_or(0) as u64)
+        .collect()
+}
+
+/// Draws `table` full-screen, with a sparkline of `energy_history` beneath it when the
+/// history is non-empty. Refuses up front when stdout is not a terminal, and restores the
+/// cursor even if drawing fails part-way.
+fn draw_table(table: Table<'static>, energy_history: &[usize]) -> Result<(), Box<dyn Error>> {
     let stdout = stdout();
+    if !stdout.is_terminal() {
+        return Err("standard output is not a terminal".into());
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let rows = cells.into_iter().map(Row::new).collect::<Vec<Row>>();
-    let widths = vec![Constraint::Length(3); columns];
-    let table = Table::new(rows, widths).block(Block::default().title(title).borders(Borders::ALL));
-    terminal.draw(|frame| {
-        frame.render_widget(table.clone(), frame.area());
-    })?;
+    let drawn = terminal.draw(|frame| {
+        if energy_history.is_empty() {
+            frame.render_widget(&table, frame.area());
+            return;
+        }
+        let [board_area, chart_area] =
+            Layout::vertical([Constraint::Min(11), Constraint::Length(6)]).areas(frame.area());
+        let data = sparkline_data(energy_history, chart_area.width.saturating_sub(2) as usize);
+        let sparkline = Sparkline::default()
+            .block(Block::default().title("Energy").borders(Borders::ALL))
+            .style(Style::default().fg(Color::Yellow))
+            .data(&data);
+        frame.render_widget(&table, board_area);
+        frame.render_widget(sparkline, chart_area);
+    })
+    .map(|_| ());
     terminal.show_cursor()?;
+    drawn?;
     Ok(())
 }
+
+/// One frame of the batch dashboard.
+pub struct DashboardView<'a> {
+    pub tally: &'a BatchTally,
+    pub total: usize,
+    /// Input index and name of the most recently finished puzzle.
+    pub latest: Option<(usize, &'a str)>,
+    pub elapsed: Duration,
+    /// Energy after each accepted swap of that puzzle's solve.
+    pub trace: &'a [usize],
+}
+
+/// A live view redrawn in place for the length of a batch. Dropping it restores the cursor,
+/// so an early return still leaves the terminal usable.
+pub struct Dashboard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl Dashboard {
+    /// Refuses up front when stdout is not a terminal, like the other TUI views.
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let stdout = stdout();
+        if !stdout.is_terminal() {
+            return Err("standard output is not a terminal".into());
+        }
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        terminal.clear()?;
+        Ok(Dashboard { terminal })
+    }
+
+    pub fn draw(&mut self, view: &DashboardView) -> Result<(), Box<dyn Error>> {
+        let rate = view
+            .tally
+            .solve_rate()
+            .map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0));
+        let latest = view
+            .latest
+            .map_or("-".to_string(), |(index, name)| format!("#{} {name}", index + 1));
+        let lines = vec![
+            Line::from(format!("Puzzles done  {}/{}", view.tally.done, view.total)),
+            Line::from(format!("Solve rate    {rate} ({} solved)", view.tally.solved)),
+            Line::from(format!("Mean swaps    {:.0}", view.tally.mean_steps)),
+            Line::from(format!("Last finished {latest}")),
+            Line::from(format!("Elapsed       {:.1?}", view.elapsed)),
+        ];
+        let stats =
+            Paragraph::new(lines).block(Block::default().title("Bench").borders(Borders::ALL));
+        self.terminal.draw(|frame| {
+            let [stats_area, chart_area] =
+                Layout::vertical([Constraint::Length(7), Constraint::Min(6)]).areas(frame.area());
+            let data = sparkline_data(view.trace, chart_area.width.saturating_sub(2) as usize);
+            let sparkline = Sparkline::default()
+                .block(Block::default().title("Energy of the last solve").borders(Borders::ALL))
+                .style(Style::default().fg(Color::Yellow))
+                .data(&data);
+            frame.render_widget(stats, stats_area);
+            frame.render_widget(sparkline, chart_area);
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for Dashboard {
+    /// Leaves the cursor below the last frame so later output does not overwrite it.
+    fn drop(&mut self) {
+        if let Ok(size) = self.terminal.size() {
+            let _ = self.terminal.set_cursor_position((0, size.height.saturating_sub(1)));
+            let _ = writeln!(self.terminal.backend_mut());
+        }
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{buffer::Buffer, widgets::Widget};
+
+    fn givens() -> [[Option<u8>; 9]; 9] {
+        let mut givens = [[None; 9]; 9];
+        givens[0][0] = Some(5);
+        givens[4][4] = Some(7);
+        givens
+    }
+
+    #[test]
+    fn background_highlight_fills_given_cells_in_ascii() {
+        colored::control::set_override(true);
+        let mut board = [[1u8; 9]; 9];
+        board[0][0] = 5;
+        let style = RenderStyle {
+            givens: GivenHighlight::Background,
+            ..RenderStyle::default()
+        };
+        let mask = [[false; 9]; 9];
+        let mut out = Vec::new();
+        write_sudoku_styled(&mut out, "t", &board, &givens(), &mask, false, &style).unwrap();
+        let filled = String::from_utf8(out).unwrap();
+        let (classic, mut out) = (RenderStyle::default(), Vec::new());
+        write_sudoku_styled(&mut out, "t", &board, &givens(), &mask, false, &classic).unwrap();
+        let colored = String::from_utf8(out).unwrap();
+        colored::control::unset_override();
+
+        assert_eq!(filled.matches("\x1b[47;30m").count(), 2);
+        assert!(filled.contains("\x1b[47;30m5\x1b[0m"));
+        assert!(!colored.contains("\x1b[47"));
+        assert_eq!(colored.matches("\x1b[36m").count(), 2);
+    }
+
+    #[test]
+    fn background_highlight_fills_given_cells_in_the_table_widget() {
+        let board = [[1u8; 9]; 9];
+        let mask = [[false; 9]; 9];
+        let area = Rect::new(0, 0, BOARD_WIDTH, BOARD_HEIGHT);
+        let background = |highlight| {
+            let mut buffer = Buffer::empty(area);
+            let table = sudoku_table_widget(&board, &givens(), &mask, false, highlight);
+            table.render(area, &mut buffer);
+            // Column `col` of the table starts after the border and `col` 4-wide columns.
+            move |row: u16, col: u16| buffer[(1 + 4 * col, 1 + row)].bg
+        };
+        let filled = background(GivenHighlight::Background);
+        assert_eq!(filled(0, 0), Color::Gray);
+        assert_eq!(filled(4, 4), Color::Gray);
+        assert_ne!(filled(0, 1), Color::Gray);
+        assert_ne!(background(GivenHighlight::Color)(0, 0), Color::Gray);
+    }
+
+    #[test]
+    fn box_shading_alternates_between_neighbouring_boxes() {
+        assert!(!is_shaded_box(0, 0) && is_shaded_box(0, 3) && !is_shaded_box(4, 4));
+        let board = [[1u8; 9]; 9];
+        let (givens, mask) = ([[None; 9]; 9], [[false; 9]; 9]);
+        let area = Rect::new(0, 0, BOARD_WIDTH, BOARD_HEIGHT);
+        let mut buffer = Buffer::empty(area);
+        let table = sudoku_table_widget(&board, &givens, &mask, true, GivenHighlight::Color);
+        table.render(area, &mut buffer);
+        let (first_box, second_box) = (buffer[(1, 1)].bg, buffer[(1 + 4 * 3, 1)].bg);
+        assert_ne!(first_box, second_box);
+        assert_eq!(second_box, Color::DarkGray);
+    }
+
+    #[test]
+    fn conflict_overlay_marks_a_single_cell() {
+        let mut mask = [[false; 9]; 9];
+        mask[4][7] = true;
+        let mut out = Vec::new();
+        write_conflict_overlay(&mut out, &mask).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let plain = text.replace("\x1b[1;31m", "").replace("\x1b[0m", "");
+        assert_eq!(plain.matches('X').count(), 1);
+        // The title and a rule before every band precede row 4 on line 7.
+        let line = plain.lines().nth(7).unwrap();
+        assert_eq!(line, "| . . . | . . . | . X . |");
+    }
+
+    #[test]
+    fn table_widget_draws_nine_by_nine_cells_into_a_sub_region() {
+        let mut board = [[0u8; 9]; 9];
+        for (row, line) in board.iter_mut().enumerate() {
+            for (col, value) in line.iter_mut().enumerate() {
+                *value = ((row + col) % 9 + 1) as u8;
+            }
+        }
+        let (givens, mask) = ([[None; 9]; 9], [[false; 9]; 9]);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, BOARD_WIDTH + 10, BOARD_HEIGHT + 4));
+        let area = Rect::new(5, 2, BOARD_WIDTH, BOARD_HEIGHT);
+        sudoku_table_widget(&board, &givens, &mask, false, GivenHighlight::Color)
+            .render(area, &mut buffer);
+        for row in 0..9u16 {
+            let digits: String = (0..9u16)
+                .map(|col| buffer[(area.x + 1 + 4 * col, area.y + 1 + row)].symbol())
+                .collect();
+            let expected: String =
+                board[row as usize].iter().map(|value| value.to_string()).collect();
+            assert_eq!(digits, expected);
+        }
+        assert_eq!(buffer[(area.x + 1, area.bottom() - 1)].symbol(), "─");
+        assert_eq!(buffer[(area.x - 1, area.y + 1)].symbol(), " ");
+    }
+
+    #[test]
+    fn sparkline_data_keeps_each_buckets_peak() {
+        assert_eq!(sparkline_data(&[], 10), Vec::<u64>::new());
+        assert_eq!(sparkline_data(&[3, 1], 0), Vec::<u64>::new());
+        assert_eq!(sparkline_data(&[5, 4, 3], 10), vec![5, 4, 3]);
+        let history = [9, 2, 2, 7, 1, 1, 0];
+        assert_eq!(sparkline_data(&history, 3), vec![9, 7, 0]);
+        assert!(sparkline_data(&[1; 100], 7).len() <= 7);
+    }
+
+    /// `text` without ANSI colour codes, which other tests may switch on at any time.
+    fn plain(text: &str) -> String {
+        let mut plain = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                plain.push(c);
+            }
+        }
+        plain
+    }
+
+    #[test]
+    fn compact_sudoku_groups_all_81_digits_by_box() {
+        let mut board = [[0u8; 9]; 9];
+        for (row, line) in board.iter_mut().enumerate() {
+            for (col, value) in line.iter_mut().enumerate() {
+                *value = ((row * 3 + row / 3 + col) % 9 + 1) as u8;
+            }
+        }
+        let mut out = Vec::new();
+        write_sudoku_compact(&mut out, "t", &board, &givens(), &[[false; 9]; 9]).unwrap();
+        let line = plain(&String::from_utf8(out).unwrap());
+        let (title, grid) = line.trim_end().split_once(": ").unwrap();
+        assert_eq!(title, "t");
+        let bands: Vec<&str> = grid.split(" / ").collect();
+        assert_eq!(bands.len(), 3);
+        assert_eq!(bands[0], "123|456|789 456|789|123 789|123|456");
+        let digits: String = grid.chars().filter(char::is_ascii_digit).collect();
+        let expected: String = board.iter().flatten().map(|value| value.to_string()).collect();
+        assert_eq!(digits, expected);
+    }
+
+    #[test]
+    fn compact_queens_prints_the_column_vector() {
+        let mut out = Vec::new();
+        write_queens_compact(&mut out, &[0, 4, 7, 5, 2, 6, 1, 3], [false; 8]).unwrap();
+        assert_eq!(plain(&String::from_utf8(out).unwrap()), "04752613\n");
+    }
+
+    #[test]
+    fn tight_and_wide_styles_set_the_cell_spacing() {
+        let mut board = [[0u8; 9]; 9];
+        for (row, line) in board.iter_mut().enumerate() {
+            for (col, cell) in line.iter_mut().enumerate() {
+                *cell = ((row * 3 + row / 3 + col) % 9 + 1) as u8;
+            }
+        }
+        let mask = [[false; 9]; 9];
+        let render = |style: &RenderStyle| {
+            let mut out = Vec::new();
+            write_sudoku_styled(&mut out, "t", &board, &givens(), &mask, false, style).unwrap();
+            plain(&String::from_utf8(out).unwrap())
+        };
+
+        let tight = render(&RenderStyle::TIGHT);
+        let rows: Vec<&str> = tight.lines().skip(1).collect();
+        assert_eq!(rows.len(), 9);
+        assert_eq!(rows[0], "123456789");
+        assert!(rows.iter().all(|row| !row.contains(' ')), "{tight}");
+
+        let wide = render(&RenderStyle::WIDE);
+        let lines: Vec<&str> = wide.lines().skip(1).collect();
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[1], "|  1  2  3  |  4  5  6  |  7  8  9  |");
+        assert_eq!(lines[0].len(), lines[1].len());
+        assert!(render(&RenderStyle::CLASSIC).contains("| 1 2 3 | 4 5 6 | 7 8 9 |"));
+    }
+
+    #[test]
+    fn board_grid_layout_gives_one_region_per_board_up_to_capacity() {
+        // Room for 3 columns and 2 rows of boards, with slack on both axes.
+        let area = Rect::new(4, 2, 3 * BOARD_WIDTH + 5, 2 * BOARD_HEIGHT + 3);
+        let regions = board_grid_layout(area, 4);
+        assert_eq!(regions.len(), 4);
+        assert_eq!(regions[0], Rect::new(4, 2, BOARD_WIDTH, BOARD_HEIGHT));
+        assert_eq!(regions[3], Rect::new(4, 2 + BOARD_HEIGHT, BOARD_WIDTH, BOARD_HEIGHT));
+
+        let full = board_grid_layout(area, 10);
+        assert_eq!(full.len(), 6);
+        for (index, region) in full.iter().enumerate() {
+            assert!(area.contains(region.as_position()) && region.right() <= area.right());
+            assert!(region.bottom() <= area.bottom());
+            assert!(full[..index].iter().all(|other| !other.intersects(*region)));
+        }
+        assert!(board_grid_layout(Rect::new(0, 0, BOARD_WIDTH - 1, 50), 3).is_empty());
+    }
+
+    #[test]
+    fn coordinate_headers_sit_over_their_columns() {
+        let mut board = [[0u8; 9]; 9];
+        for (row, line) in board.iter_mut().enumerate() {
+            for (col, cell) in line.iter_mut().enumerate() {
+                *cell = ((row * 3 + row / 3 + col) % 9 + 1) as u8;
+            }
+        }
+        let mask = [[false; 9]; 9];
+        for base in [RenderStyle::CLASSIC, RenderStyle::TIGHT, RenderStyle::WIDE] {
+            let style = RenderStyle { coords: true, ..base };
+            let mut out = Vec::new();
+            write_sudoku_styled(&mut out, "t", &board, &givens(), &mask, false, &style).unwrap();
+            let text = plain(&String::from_utf8(out).unwrap());
+            let lines: Vec<&str> = text.lines().skip(1).collect();
+            let header = lines[0];
+            let digits = |line: &str| -> Vec<usize> {
+                line.char_indices().filter(|(_, c)| c.is_ascii_digit()).map(|(i, _)| i).collect()
+            };
+            let numbers: String = header.chars().filter(|c| !c.is_whitespace()).collect();
+            assert_eq!(numbers, "123456789", "{text}");
+            let rows: Vec<&&str> = lines[1..].iter().filter(|line| !line.contains('-')).collect();
+            assert_eq!(rows.len(), 9);
+            for (index, row) in rows.iter().enumerate() {
+                assert!(row.starts_with(&format!("{} ", index + 1)), "{text}");
+                // Past the row label, each digit lines up under its column number.
+                assert_eq!(digits(row)[1..], digits(header)[..], "{text}");
+            }
+        }
+    }
+}
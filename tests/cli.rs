@@ -0,0 +1,510 @@
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+use thermodynamic_computing::{seeds, sudoku, ui};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ThermodynamimcComputing"))
+        .arg("--no-color")
+        .args(args)
+        .output()
+        .expect("failed to run the binary")
+}
+
+/// `run` with `input` piped to the binary's standard input.
+fn run_with_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ThermodynamimcComputing"))
+        .arg("--no-color")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the binary");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// A path in the temp directory, unique to this test process.
+fn scratch(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tc-cli-{}-{name}", std::process::id()))
+}
+
+fn read_json(path: &PathBuf) -> serde_json::Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// `givens` as the 81-character line `--puzzle` and `--stdin` read.
+fn givens_line(givens: &[[Option<u8>; 9]; 9]) -> String {
+    givens
+        .iter()
+        .flatten()
+        .map(|given| given.map_or('.', |value| char::from(b'0' + value)))
+        .collect()
+}
+
+#[test]
+fn gen_seed_fixes_the_puzzle_while_solve_seeds_vary_the_trace() {
+    let mut reports = Vec::new();
+    for solve_seed in ["1", "2"] {
+        let (json, trace) = (scratch(&format!("split-{solve_seed}.json")), scratch("split.csv"));
+        let output = run(&[
+            "sudoku",
+            "--gen-seed",
+            "5",
+            "--solve-seed",
+            solve_seed,
+            "--max-steps",
+            "2000",
+            "--json",
+            json.to_str().unwrap(),
+            "--trace",
+            trace.to_str().unwrap(),
+        ]);
+        assert!(output.status.code().is_some_and(|code| code < 2), "{output:?}");
+        let trace_text = fs::read_to_string(&trace).unwrap();
+        // Skip the provenance line, whose timestamp may differ.
+        let points: Vec<String> = trace_text.lines().skip(1).map(str::to_string).collect();
+        reports.push((read_json(&json), points));
+        fs::remove_file(json).unwrap();
+        fs::remove_file(trace).unwrap();
+    }
+    let [(first, first_trace), (second, second_trace)] = &reports[..] else {
+        unreachable!()
+    };
+    assert_eq!(first["givens"], second["givens"]);
+    assert_ne!(first_trace, second_trace);
+    for (report, solve_seed) in [(first, 1), (second, 2)] {
+        assert_eq!(report["provenance"]["gen_seed"], 5);
+        assert_eq!(report["provenance"]["solve_seed"], solve_seed);
+    }
+}
+
+#[test]
+fn timeout_actions_follow_the_exit_code_contract() {
+    let unsolved = |extra: &[&str]| {
+        let base = ["sudoku", "--seed", "3", "--holes", "55", "--max-steps", "50"];
+        run(&[&base[..], extra].concat())
+    };
+
+    let best = unsolved(&["--timeout-action", "best"]);
+    assert_eq!(best.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&best.stdout).contains("Result: best effort"));
+
+    let error = unsolved(&["--timeout-action", "error"]);
+    assert_eq!(error.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&error.stderr).contains("--timeout-action error"));
+
+    let restart = unsolved(&["--timeout-action", "restart", "--max-retries", "1"]);
+    assert_eq!(restart.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&restart.stdout).contains("Restart:"));
+
+    // Only --fail-if-energy-above turns a best-effort board into a pass.
+    let accepted = unsolved(&["--timeout-action", "best", "--fail-if-energy-above", "200"]);
+    assert_eq!(accepted.status.code(), Some(0));
+
+    let solved = run(&["sudoku", "--seed", "5", "--timeout-action", "error"]);
+    assert_eq!(solved.status.code(), Some(0));
+}
+
+#[test]
+fn verify_passes_on_a_genuine_solution() {
+    let output = run(&["sudoku", "--seed", "5", "--verify"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Verified:"));
+}
+
+#[test]
+fn exit_codes_separate_solved_best_effort_and_errors() {
+    assert_eq!(run(&["sudoku", "--seed", "5"]).status.code(), Some(0));
+    let starved = run(&["sudoku", "--seed", "1", "--holes", "60", "--max-steps", "10"]);
+    assert_eq!(starved.status.code(), Some(1));
+    assert_eq!(run(&["sudoku", "--holes", "many"]).status.code(), Some(2));
+    assert_eq!(run(&["sudoku", "--puzzle", "123"]).status.code(), Some(2));
+}
+
+#[test]
+fn writers_match_the_stdout_rendering_without_color() {
+    colored::control::set_override(false);
+    let puzzle = sudoku::SudokuPuzzle::reproduce(5, 48);
+    let mut expected = Vec::new();
+    ui::write_given_grid(&mut expected, &puzzle.givens).unwrap();
+    let output = run(&["sudoku", "--seed", "5"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&String::from_utf8(expected).unwrap()), "{stdout}");
+}
+
+#[test]
+fn a_puzzle_on_stdin_is_solved() {
+    let line = givens_line(&sudoku::SudokuPuzzle::reproduce(5, 30).givens);
+    let output = run_with_stdin(&["sudoku", "--stdin", "--seed", "1"], &format!("{line}\n"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "{stdout}");
+    assert!(stdout.contains("puzzle loaded (holes=30, givens=51"), "{stdout}");
+    assert!(stdout.contains("Result: solved"));
+}
+
+#[test]
+fn sixty_five_holes_warn_only_when_allowed() {
+    let allowed = run(&["sudoku", "--seed", "2", "--holes", "65", "--allow-nonunique"]);
+    let stdout = String::from_utf8_lossy(&allowed.stdout);
+    assert!(stdout.contains("Warning: only 16 givens (fewer than 17)"), "{stdout}");
+
+    let puzzle = format!("{}{}", "123456789", ".".repeat(72));
+    let refused = run(&["sudoku", "--puzzle", &puzzle]);
+    assert_eq!(refused.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("--allow-nonunique"));
+}
+
+#[test]
+fn json_reports_carry_the_integer_schema_version() {
+    let path = scratch("schema.json");
+    let json = path.to_str().unwrap();
+    for args in [
+        &["sudoku", "--seed", "5", "--json", json][..],
+        &["queens", "--seed", "5", "--solutions", "2", "--json", json][..],
+    ] {
+        assert_eq!(run(args).status.code(), Some(0));
+        let report = read_json(&path);
+        assert!(report["schema_version"].is_u64(), "{report}");
+        assert_eq!(report["schema_version"], thermodynamic_computing::export::JSON_SCHEMA_VERSION);
+    }
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn schedule_comparison_prints_one_row_per_schedule() {
+    let output = run(&["sudoku", "--seed", "5", "--max-steps", "3000", "--compare-schedules"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let table: Vec<&str> =
+        stdout.lines().skip_while(|line| !line.starts_with("schedule")).collect();
+    let names = ["geometric", "linear", "exponential", "logarithmic", "adaptive", "reverse"];
+    for (row, name) in names.into_iter().enumerate() {
+        assert!(table[row + 1].starts_with(name), "{stdout}");
+    }
+    assert!(table.get(7).is_none_or(|line| line.starts_with("Fastest:")), "{stdout}");
+}
+
+#[test]
+fn acceptance_window_is_reported_after_an_adaptive_run() {
+    let args = ["--max-steps", "3000", "--schedule", "adaptive", "--acceptance-window", "100"];
+    let output = run(&[&["sudoku", "--seed", "5"][..], &args[..]].concat());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("over the last 100 swaps"), "{stdout}");
+}
+
+#[test]
+fn queens_refuses_the_adaptive_schedule() {
+    for schedule in ["adaptive", "adaptive:0.3:0.05"] {
+        let output = run(&["queens", "--schedule", schedule]);
+        assert_eq!(output.status.code(), Some(2), "{schedule}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("no acceptance ratio"), "{stderr}");
+    }
+    let output = run(&["queens", "--seed", "3", "--solutions", "1", "--schedule", "exponential"]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn seed_list_prints_one_line_per_seed() {
+    let output = run(&["sudoku", "--seed-list", "5,1,5"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "{stdout}");
+    for (line, seed) in lines.iter().zip(["5", "1", "5"]) {
+        assert!(line.starts_with(&format!("seed {seed}: ")), "{line}");
+    }
+    // The listed seed behaves exactly like `--seed 5`.
+    assert!(lines[0].contains("solved after 4542 swaps"), "{}", lines[0]);
+    assert_eq!(lines[0].split(" (").next(), lines[2].split(" (").next());
+
+    let output = run(&["queens", "--seed-list", "3,17", "--solutions", "2"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let seeds: Vec<&str> = stdout.lines().map(|line| line.split(':').next().unwrap()).collect();
+    assert_eq!(seeds, ["seed 3", "seed 17"]);
+}
+
+#[test]
+fn symmetry_report_counts_every_found_solution_once() {
+    let output = run(&["queens", "--seed", "1", "--solutions", "10", "--symmetry-report"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("class"))
+        .skip(1)
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows.len(), 12, "{stdout}");
+    let found: usize = rows.iter().map(|row| row[3].parse::<usize>().unwrap()).sum();
+    assert_eq!(found, 10);
+    let small: Vec<&Vec<&str>> = rows.iter().filter(|row| row[2] == "4").collect();
+    assert_eq!(small.len(), 1);
+    assert_eq!(small[0][1], "24170635");
+}
+
+/// The `title` line and the 13-line grid that follows it.
+fn board_block<'a>(stdout: &'a str, title: &str) -> Vec<&'a str> {
+    stdout.lines().skip_while(|line| !line.starts_with(title)).take(14).collect()
+}
+
+#[test]
+fn replay_redraws_the_saved_boards() {
+    let json = scratch("replay.json");
+    let original = run(&["sudoku", "--seed", "5", "--json", json.to_str().unwrap()]);
+    let replayed = run(&["replay", json.to_str().unwrap()]);
+    fs::remove_file(&json).unwrap();
+    assert_eq!(replayed.status.code(), Some(0), "{replayed:?}");
+    let (original, replayed) = (
+        String::from_utf8_lossy(&original.stdout),
+        String::from_utf8_lossy(&replayed.stdout),
+    );
+    for title in ["Sudoku puzzle (", "Final Sudoku state"] {
+        let block = board_block(&original, title);
+        assert_eq!(block.len(), 14, "{original}");
+        assert_eq!(board_block(&replayed, title), block);
+    }
+}
+
+#[test]
+fn strict_givens_refuse_a_clash_that_lenient_mode_anneals() {
+    let mut givens = sudoku::SudokuPuzzle::reproduce(5, 40).givens;
+    let given_cols: Vec<usize> = (0..9).filter(|&col| givens[0][col].is_some()).collect();
+    givens[0][given_cols[1]] = givens[0][given_cols[0]];
+    let line = givens_line(&givens);
+
+    let strict = run(&["sudoku", "--puzzle", &line, "--max-steps", "500"]);
+    assert_eq!(strict.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&strict.stderr).contains("givens clash"));
+
+    let args = ["sudoku", "--puzzle", &line, "--max-steps", "500", "--strict-givens", "false"];
+    let lenient = run(&args);
+    let stdout = String::from_utf8_lossy(&lenient.stdout);
+    assert_eq!(lenient.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("Warning: givens clash"));
+    assert!(stdout.contains("best effort after 500 swaps"), "{stdout}");
+}
+
+#[test]
+fn success_rate_counts_the_solved_seeds() {
+    let args = ["success-rate", "--runs", "4", "--first-seed", "3", "--max-steps", "6000"];
+    let output = run(&args);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let runs: Vec<&str> = stdout.lines().filter(|line| line.starts_with("seed ")).collect();
+    assert_eq!(runs.len(), 4, "{stdout}");
+    let solved = runs.iter().filter(|line| line.contains(": solved after")).count();
+    let summary = stdout.lines().last().unwrap();
+    assert!(summary.starts_with(&format!("Success rate: {solved}/4 solved")), "{summary}");
+    assert!(runs[2].starts_with("seed 5: solved after 4542 swaps"), "{stdout}");
+}
+
+#[test]
+fn tui_without_a_terminal_degrades_to_ascii() {
+    let output = run(&["sudoku", "--seed", "5", "--tui", "--degrade-gracefully"]);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("Warning: TUI unavailable").count(), 1, "{stderr}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(board_block(&stdout, "Final Sudoku state").len(), 14, "{stdout}");
+}
+
+#[test]
+fn determinism_check_passes_on_a_seeded_solve() {
+    let output = run(&["sudoku", "--seed", "5", "--determinism-check"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Deterministic: "));
+    let unseeded = run(&["sudoku", "--determinism-check"]);
+    assert_eq!(unseeded.status.code(), Some(2));
+}
+
+#[test]
+fn json_stream_emits_one_object_per_puzzle() {
+    let line = |seed| givens_line(&sudoku::SudokuPuzzle::reproduce(seed, 40).givens);
+    let lines: Vec<String> = (1..=3).map(line).collect();
+    let file = scratch("stream.txt");
+    let text = format!("# three puzzles\n{}\nnamed {}\n{}\n", lines[0], lines[1], lines[2]);
+    fs::write(&file, text).unwrap();
+    let args = ["bench", "--puzzle-file", file.to_str().unwrap(), "--json-stream", "--jobs", "2"];
+    let output = run(&args);
+    fs::remove_file(&file).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let objects: Vec<serde_json::Value> =
+        stdout.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(objects.len(), 3, "{stdout}");
+    let mut names: Vec<(u64, &str)> = objects
+        .iter()
+        .map(|object| (object["index"].as_u64().unwrap(), object["name"].as_str().unwrap()))
+        .collect();
+    names.sort();
+    assert_eq!(names, [(0, "line-002"), (1, "named"), (2, "line-004")]);
+}
+
+/// Cells of a rendered grid block: digits, with `None` for `.`.
+fn grid_cells(block: &[&str]) -> Vec<Vec<Option<u8>>> {
+    block
+        .iter()
+        .filter(|line| line.starts_with('|'))
+        .map(|line| {
+            line.split_whitespace()
+                .filter(|token| *token != "|")
+                .map(|token| token.parse().ok())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn shown_initial_board_keeps_the_givens_and_reports_its_energy() {
+    let output = run(&["sudoku", "--seed", "5", "--show-initial"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let givens = grid_cells(&board_block(&stdout, "Sudoku puzzle ("));
+    let initial_block = board_block(&stdout, "Initial Sudoku state");
+    let initial = grid_cells(&initial_block);
+    assert_eq!((givens.len(), initial.len()), (9, 9), "{stdout}");
+
+    let mut board = [[0u8; 9]; 9];
+    for (row, line) in initial.iter().enumerate() {
+        for (col, value) in line.iter().enumerate() {
+            board[row][col] = value.unwrap();
+            assert!(givens[row][col].is_none_or(|given| Some(given) == *value));
+        }
+    }
+    let title = format!("Initial Sudoku state (energy={})", sudoku::board_energy(&board));
+    assert_eq!(initial_block[0], title);
+}
+
+#[test]
+fn watch_runs_the_requested_iterations_on_fresh_seeds() {
+    let args = ["sudoku", "--watch", "0", "--iterations", "3", "--seed", "7", "--compact"];
+    let output = run(&args);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let headers: Vec<&str> = stdout.lines().filter(|line| line.starts_with("Watch #")).collect();
+    let expected: Vec<String> = seeds::derive_seeds(7, 3)
+        .iter()
+        .enumerate()
+        .map(|(index, seed)| format!("Watch #{} (seed {seed})", index + 1))
+        .collect();
+    assert_eq!(headers, expected);
+    assert!(stdout.lines().last().unwrap().starts_with("Watch: solved "), "{stdout}");
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_stops_on_ctrl_c_after_the_puzzle_in_progress() {
+    let child = Command::new(env!("CARGO_BIN_EXE_ThermodynamimcComputing"))
+        .args(["--no-color", "sudoku", "--watch", "30", "--seed", "7", "--compact"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run the binary");
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    let pid = child.id().to_string();
+    assert!(Command::new("kill").args(["-INT", &pid]).status().unwrap().success());
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("Watch #").count(), 1, "{stdout}");
+    assert!(stdout.contains("Watch: solved "), "{stdout}");
+}
+
+#[test]
+fn no_generate_requires_an_explicit_puzzle() {
+    let missing = run(&["sudoku", "--no-generate", "--seed", "5"]);
+    assert_eq!(missing.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("--no-generate"));
+
+    let line = givens_line(&sudoku::SudokuPuzzle::reproduce(5, 30).givens);
+    let output = run(&["sudoku", "--no-generate", "--puzzle", &line, "--seed", "5"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "{stdout}");
+    assert!(stdout.contains("solved after"), "{stdout}");
+}
+
+#[test]
+fn sorted_solutions_print_in_the_same_order_for_any_seed() {
+    let boards = |seed| {
+        let args = ["queens", "--all-solutions", "--sort", "--format", "compact", "--seed", seed];
+        let stdout = String::from_utf8(run(&args).stdout).unwrap();
+        let compact = |line: &&str| line.len() == 8 && line.bytes().all(|b| b.is_ascii_digit());
+        stdout.lines().filter(compact).map(str::to_string).collect::<Vec<_>>()
+    };
+    let first = boards("1");
+    assert_eq!(first.len(), 92);
+    assert!(first.is_sorted());
+    assert_eq!(first, boards("2"));
+}
+
+#[test]
+fn energy_prints_the_bare_conflict_count() {
+    let solution =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+    let output = run(&["energy", "--puzzle", solution]);
+    assert_eq!((output.status.code(), &output.stdout[..]), (Some(0), &b"0\n"[..]));
+
+    // Swapping two cells of a row duplicates a digit in each of their columns. One column
+    // duplicate alone is impossible while the boxes hold every digit, so the minimum is 2.
+    let swapped = format!("35{}", &solution[2..]);
+    let output = run_with_stdin(&["energy", "--stdin"], &swapped);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n");
+
+    let holed = format!(".{}", &solution[1..]);
+    let output = run(&["energy", "--puzzle", &holed]);
+    assert_ne!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn logged_clock_seed_replays_the_run() {
+    let budget = ["--max-steps", "3000", "--compact"];
+    let timed = run(&[&["sudoku", "--seed-from-time"][..], &budget].concat());
+    let timed = String::from_utf8(timed.stdout).unwrap();
+    let (first, rest) = timed.split_once('\n').unwrap();
+    let seed = first.rsplit(' ').next().unwrap();
+    assert_eq!(first, format!("Seed: {seed} from the clock; rerun with --seed {seed}"));
+
+    let replay = run(&[&["sudoku", "--seed", seed][..], &budget].concat());
+    // Wall-clock durations follow the step count in parentheses; drop them.
+    let untimed = |text: &str| -> Vec<String> {
+        text.lines().map(|line| line.split(" (").next().unwrap().to_string()).collect()
+    };
+    assert_eq!(untimed(rest), untimed(&String::from_utf8(replay.stdout).unwrap()));
+}
+
+#[test]
+fn energy_threshold_fails_only_runs_above_it() {
+    let solved = run(&["--fail-if-energy-above", "0", "sudoku", "--seed", "5"]);
+    assert_eq!(solved.status.code(), Some(0));
+
+    let short = ["sudoku", "--seed", "5", "--max-steps", "500", "--fail-if-energy-above"];
+    let strict = run(&[&short[..], &["0"]].concat());
+    assert_eq!(strict.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&strict.stderr);
+    assert!(stderr.contains("is above --fail-if-energy-above 0"), "{stderr}");
+
+    let lenient = run(&[&short[..], &["1000"]].concat());
+    assert_eq!(lenient.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&lenient.stdout);
+    assert!(stdout.contains("is within --fail-if-energy-above 1000"), "{stdout}");
+}
+
+#[test]
+fn reproduce_rebuilds_the_cli_puzzle_including_the_hole_clamp() {
+    for (seed, holes) in [(1, 30), (3, 70), (9, 0), (12, 48)] {
+        let (seed_arg, holes_arg) = (seed.to_string(), holes.to_string());
+        let args = ["sudoku", "--seed", &seed_arg, "--holes", &holes_arg, "--max-steps", "10"];
+        let stdout = String::from_utf8(run(&args).stdout).unwrap();
+        let printed = grid_cells(&board_block(&stdout, "Sudoku puzzle ("));
+        let givens = sudoku::SudokuPuzzle::reproduce(seed, holes).givens;
+        assert_eq!(printed, givens.map(Vec::from).to_vec(), "seed {seed}, holes {holes}");
+    }
+}
+
+#[test]
+fn invalid_time_caps_are_usage_errors() {
+    for seconds in ["-1", "NaN", "inf", "1e30"] {
+        let output = run(&["queens", &format!("--max-total-secs={seconds}")]);
+        assert_eq!(output.status.code(), Some(2), "{seconds}");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("number of seconds"));
+    }
+    assert_eq!(run(&["sudoku", "--watch=-1"]).status.code(), Some(2));
+}